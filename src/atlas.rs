@@ -0,0 +1,273 @@
+//! Dynamic atlas allocator with deallocation.
+//!
+//! Free space is kept as a set of non-overlapping rects produced by guillotine splits;
+//! neighbouring free rects sharing a whole edge are merged back on deallocation.
+
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use crate::{Rect, Vector};
+
+/// An allocation handle. Slots are reused, but a stale id never refers to a later allocation.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct AllocId {
+    index: usize,
+    generation: u32,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct AtlasMove {
+    pub id: AllocId,
+    pub from: Rect,
+    pub to: Rect,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Area {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+impl Area {
+    fn square(self) -> u32 { self.w * self.h }
+
+    fn merge(self, other: Area) -> Option<Area> {
+        if self.x == other.x && self.w == other.w {
+            if self.y + self.h == other.y { return Some(Area { h: self.h + other.h, ..self }); }
+            if other.y + other.h == self.y { return Some(Area { h: self.h + other.h, ..other }); }
+        }
+        if self.y == other.y && self.h == other.h {
+            if self.x + self.w == other.x { return Some(Area { w: self.w + other.w, ..self }); }
+            if other.x + other.w == self.x { return Some(Area { w: self.w + other.w, ..other }); }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    generation: u32,
+    area: Option<Area>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AtlasAllocator {
+    container: Rect,
+    free: Vec<Area>,
+    allocated: Vec<Slot>,
+    vacant: Vec<usize>,
+}
+
+impl AtlasAllocator {
+    pub fn new(container: Rect) -> Self {
+        let w = container.w() as u16 as u32;
+        let h = container.h() as u16 as u32;
+        let mut free = Vec::new();
+        if w != 0 && h != 0 {
+            free.push(Area { x: 0, y: 0, w, h });
+        }
+        AtlasAllocator { container, free, allocated: Vec::new(), vacant: Vec::new() }
+    }
+
+    pub fn container(&self) -> Rect { self.container }
+
+    fn rect(&self, a: Area) -> Rect {
+        let tl = self.container.tl.offset(Vector { x: a.x as u16 as i16, y: a.y as u16 as i16 });
+        Rect { tl, size: Vector { x: a.w as u16 as i16, y: a.h as u16 as i16 } }
+    }
+
+    fn take(&mut self, w: u32, h: u32) -> Option<Area> {
+        let (i, _) = self.free.iter().enumerate()
+            .filter(|(_, f)| f.w >= w && f.h >= h)
+            .min_by_key(|(_, f)| (f.square(), f.w.min(f.h)))?;
+        let f = self.free.swap_remove(i);
+        let (rest_w, rest_h) = (f.w - w, f.h - h);
+        let (right, bottom) = if rest_w > rest_h {
+            (Area { x: f.x + w, y: f.y, w: rest_w, h: f.h }, Area { x: f.x, y: f.y + h, w, h: rest_h })
+        } else {
+            (Area { x: f.x + w, y: f.y, w: rest_w, h }, Area { x: f.x, y: f.y + h, w: f.w, h: rest_h })
+        };
+        for a in [right, bottom] {
+            if a.w != 0 && a.h != 0 { self.free.push(a); }
+        }
+        Some(Area { x: f.x, y: f.y, w, h })
+    }
+
+    fn coalesce(&mut self, mut area: Area) {
+        'merge: loop {
+            for i in 0 .. self.free.len() {
+                if let Some(merged) = area.merge(self.free[i]) {
+                    self.free.swap_remove(i);
+                    area = merged;
+                    continue 'merge;
+                }
+            }
+            break;
+        }
+        self.free.push(area);
+    }
+
+    /// Returns `None` if there is no free rect large enough, or if the `size` is empty.
+    pub fn allocate(&mut self, size: Vector) -> Option<(AllocId, Rect)> {
+        let w = size.x as u16 as u32;
+        let h = size.y as u16 as u32;
+        if w == 0 || h == 0 { return None; }
+        let area = self.take(w, h)?;
+        let index = if let Some(index) = self.vacant.pop() {
+            self.allocated[index].area = Some(area);
+            index
+        } else {
+            self.allocated.push(Slot { generation: 0, area: Some(area) });
+            self.allocated.len() - 1
+        };
+        Some((AllocId { index, generation: self.allocated[index].generation }, self.rect(area)))
+    }
+
+    fn slot(&self, id: AllocId) -> Option<&Slot> {
+        self.allocated.get(id.index).filter(|x| x.generation == id.generation)
+    }
+
+    /// Returns the freed rect, or `None` if `id` is not allocated.
+    pub fn deallocate(&mut self, id: AllocId) -> Option<Rect> {
+        let area = self.slot(id)?.area?;
+        let slot = &mut self.allocated[id.index];
+        slot.area = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.vacant.push(id.index);
+        self.coalesce(area);
+        Some(self.rect(area))
+    }
+
+    /// Returns `None` if `id` is not allocated.
+    pub fn get(&self, id: AllocId) -> Option<Rect> {
+        self.slot(id)?.area.map(|a| self.rect(a))
+    }
+
+    pub fn allocations(&self) -> impl Iterator<Item=(AllocId, Rect)> + '_ {
+        self.allocated.iter().enumerate().filter_map(|(index, x)|
+            x.area.map(|a| (AllocId { index, generation: x.generation }, self.rect(a)))
+        )
+    }
+
+    pub fn free_rects(&self) -> impl Iterator<Item=Rect> + '_ {
+        self.free.iter().map(|&a| self.rect(a))
+    }
+
+    pub fn free_area(&self) -> u32 {
+        self.free.iter().map(|a| a.square()).sum()
+    }
+
+    pub fn largest_free_rect(&self) -> Option<Rect> {
+        self.free.iter().max_by_key(|a| a.square()).map(|&a| self.rect(a))
+    }
+
+    /// `1 - largest free rect area / total free area`: zero if all free space is one rect.
+    pub fn fragmentation(&self) -> f32 {
+        let total = self.free_area();
+        if total == 0 { return 0.0; }
+        let largest = self.free.iter().map(|a| a.square()).max().unwrap_or(0);
+        1.0 - (largest as f32) / (total as f32)
+    }
+
+    /// Reallocates all live allocations from scratch, larger ones first.
+    ///
+    /// Keeps allocation ids. Returns the moved allocations, or `None` (leaving `self` unchanged)
+    /// if not every allocation fits after repacking.
+    pub fn repack(&mut self) -> Option<Vec<AtlasMove>> {
+        let mut live: Vec<(usize, Area)> = self.allocated.iter().enumerate()
+            .filter_map(|(i, x)| x.area.map(|a| (i, a)))
+            .collect();
+        live.sort_by_key(|&(i, a)| (Reverse((a.w.max(a.h), a.w.min(a.h))), i));
+        let mut packed = AtlasAllocator::new(self.container);
+        packed.allocated = self.allocated.iter().map(|x| Slot { area: None, ..*x }).collect();
+        packed.vacant = self.vacant.clone();
+        let mut moves = Vec::new();
+        for (index, a) in live {
+            let new = packed.take(a.w, a.h)?;
+            packed.allocated[index].area = Some(new);
+            if new != a {
+                let id = AllocId { index, generation: packed.allocated[index].generation };
+                moves.push(AtlasMove { id, from: self.rect(a), to: self.rect(new) });
+            }
+        }
+        *self = packed;
+        Some(moves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use quickcheck_macros::quickcheck;
+    use crate::*;
+    use crate::atlas::*;
+
+    fn disjoint(rects: &[Rect]) -> bool {
+        rects.iter().enumerate().all(|(i, a)| rects[i + 1 ..].iter().all(|b| a.intersect(*b).is_empty()))
+    }
+
+    #[quickcheck]
+    fn atlas_allocations_do_not_intersect(tl: Point, ops: Vec<(bool, u8, u8)>) -> bool {
+        let container = Rect { tl, size: Vector { x: 64, y: 48 } };
+        let mut atlas = AtlasAllocator::new(container);
+        let mut ids = Vec::new();
+        for (alloc, w, h) in ops {
+            if alloc || ids.is_empty() {
+                if let Some((id, _)) = atlas.allocate(Vector { x: (w % 24) as i16, y: (h % 24) as i16 }) {
+                    ids.push(id);
+                }
+            } else {
+                let id = ids.swap_remove(w as usize % ids.len());
+                atlas.deallocate(id).unwrap();
+            }
+        }
+        let mut rects: Vec<Rect> = atlas.allocations().map(|x| x.1).collect();
+        let used: u32 = rects.iter().map(|x| x.area()).sum();
+        rects.extend(atlas.free_rects());
+        disjoint(&rects) && used + atlas.free_area() == container.area()
+    }
+
+    #[test]
+    fn atlas_coalesces_free_space() {
+        let container = Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: 8, y: 8 } };
+        let mut atlas = AtlasAllocator::new(container);
+        let ids: Vec<_> = (0 .. 4).map(|_| atlas.allocate(Vector { x: 4, y: 4 }).unwrap().0).collect();
+        assert!(atlas.allocate(Vector { x: 1, y: 1 }).is_none());
+        for id in ids {
+            atlas.deallocate(id).unwrap();
+        }
+        assert_eq!(atlas.largest_free_rect(), Some(container));
+        assert_eq!(atlas.fragmentation(), 0.0);
+    }
+
+    #[test]
+    fn atlas_rejects_stale_id() {
+        let container = Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: 8, y: 8 } };
+        let mut atlas = AtlasAllocator::new(container);
+        let (stale, _) = atlas.allocate(Vector { x: 4, y: 4 }).unwrap();
+        atlas.deallocate(stale).unwrap();
+        let (id, rect) = atlas.allocate(Vector { x: 2, y: 2 }).unwrap();
+        assert_ne!(id, stale);
+        assert_eq!(atlas.get(stale), None);
+        assert_eq!(atlas.deallocate(stale), None);
+        assert_eq!(atlas.get(id), Some(rect));
+        assert_eq!(atlas.deallocate(id), Some(rect));
+    }
+
+    #[test]
+    fn atlas_repack() {
+        let container = Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: 8, y: 2 } };
+        let mut atlas = AtlasAllocator::new(container);
+        let a = atlas.allocate(Vector { x: 2, y: 2 }).unwrap().0;
+        let b = atlas.allocate(Vector { x: 2, y: 2 }).unwrap().0;
+        atlas.allocate(Vector { x: 2, y: 2 }).unwrap();
+        atlas.deallocate(a);
+        assert!(atlas.fragmentation() > 0.0);
+        let moves = atlas.repack().unwrap();
+        assert!(moves.iter().any(|m| m.id == b));
+        assert_eq!(atlas.fragmentation(), 0.0);
+        assert_eq!(atlas.largest_free_rect().map(|x| x.w()), Some(4));
+        assert_eq!(atlas.allocate(Vector { x: 4, y: 2 }).map(|x| x.1.l()), Some(4));
+    }
+}
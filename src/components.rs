@@ -0,0 +1,123 @@
+//! Connected component labeling.
+
+use alloc::vec::Vec;
+use enum_derive_2018::{EnumDisplay, EnumFromStr};
+use macro_attr_2018::macro_attr;
+use crate::{Point, Rect, Vector};
+use crate::grid::DenseGrid;
+
+macro_attr! {
+    /// `Four` connects cells sharing a side, `Eight` also connects diagonal neighbours.
+    #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
+    #[derive(EnumDisplay!, EnumFromStr!)]
+    pub enum Connectivity { Four, Eight }
+}
+
+impl Connectivity {
+    fn neighbours(self) -> &'static [(i32, i32)] {
+        match self {
+            Connectivity::Four => &[(1, 0), (0, 1), (-1, 0), (0, -1)],
+            Connectivity::Eight => &[(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Component {
+    /// The number of cells.
+    pub size: u32,
+    pub bounds: Rect,
+    /// The first component cell in the row-major order.
+    pub seed: Point,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Components {
+    /// The index in `components` for every cell, `None` for unset ones.
+    pub labels: DenseGrid<Option<usize>>,
+    /// Components in the order of their seeds.
+    pub components: Vec<Component>,
+}
+
+/// Labels connected components of the `bounds` points where `mask` is true.
+pub fn label_components(bounds: Rect, mut mask: impl FnMut(Point) -> bool, connectivity: Connectivity) -> Components {
+    let w = bounds.w() as u16 as i64;
+    let h = bounds.h() as u16 as i64;
+    let set: Vec<bool> = if bounds.is_empty() { Vec::new() } else { bounds.points().map(&mut mask).collect() };
+    let mut labels: Vec<Option<usize>> = set.iter().map(|_| None).collect();
+    let mut components = Vec::new();
+    let mut stack = Vec::new();
+    for start in 0 .. set.len() {
+        if !set[start] || labels[start].is_some() { continue; }
+        let label = components.len();
+        labels[start] = Some(label);
+        stack.push(start);
+        let (mut size, mut l, mut t, mut r, mut b) = (0, w, h, 0, 0);
+        while let Some(i) = stack.pop() {
+            let (x, y) = (i as i64 % w, i as i64 / w);
+            size += 1;
+            l = l.min(x);
+            t = t.min(y);
+            r = r.max(x + 1);
+            b = b.max(y + 1);
+            for &(dx, dy) in connectivity.neighbours() {
+                let (nx, ny) = (x + dx as i64, y + dy as i64);
+                if nx < 0 || ny < 0 || nx >= w || ny >= h { continue; }
+                let n = (ny * w + nx) as usize;
+                if set[n] && labels[n].is_none() {
+                    labels[n] = Some(label);
+                    stack.push(n);
+                }
+            }
+        }
+        let local = |x: i64, y: i64| bounds.tl.offset(Vector { x: x as u16 as i16, y: y as u16 as i16 });
+        components.push(Component {
+            size,
+            bounds: Rect { tl: local(l, t), size: Vector { x: (r - l) as u16 as i16, y: (b - t) as u16 as i16 } },
+            seed: local(start as i64 % w, start as i64 / w),
+        });
+    }
+    let mut labels = labels.into_iter();
+    Components { labels: DenseGrid::from_fn(bounds, |_| labels.next().unwrap()), components }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use quickcheck_macros::quickcheck;
+    use crate::*;
+    use crate::components::*;
+    use crate::grid::{BitGrid, Grid};
+
+    #[quickcheck]
+    fn components_are_consistent(mask: BitGrid, eight: bool) -> bool {
+        let bounds = mask.bounds();
+        let connectivity = if eight { Connectivity::Eight } else { Connectivity::Four };
+        let res = label_components(bounds, |p| mask.get(p), connectivity);
+        let neighbours_agree = bounds.points().all(|p| {
+            let label = res.labels[p];
+            label.is_some() == mask.get(p) && connectivity.neighbours().iter().all(|&(dx, dy)| {
+                let n = p.offset(Vector { x: dx as i16, y: dy as i16 });
+                res.labels.get(n).is_none_or(|&m| m.is_none() || label.is_none() || m == label)
+            })
+        });
+        let components_agree = res.components.iter().enumerate().all(|(i, c)| {
+            let cells: Vec<_> = bounds.points().filter(|&p| res.labels[p] == Some(i)).collect();
+            cells.len() as u32 == c.size && cells[0] == c.seed &&
+                cells.iter().all(|&p| c.bounds.contains(p)) &&
+                cells.iter().any(|p| p.x == c.bounds.l()) && cells.iter().any(|p| p.y == c.bounds.t()) &&
+                cells.iter().any(|p| p.x == c.bounds.r_inner()) && cells.iter().any(|p| p.y == c.bounds.b_inner())
+        });
+        neighbours_agree && components_agree
+    }
+
+    #[test]
+    fn diagonal_components() {
+        let bounds = Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: 4, y: 4 } };
+        let checker = |p: Point| (p.x + p.y) % 2 == 0;
+        assert_eq!(label_components(bounds, checker, Connectivity::Four).components.len(), 8);
+        let eight = label_components(bounds, checker, Connectivity::Eight);
+        assert_eq!(eight.components, [Component { size: 8, bounds, seed: Point { x: 0, y: 0 } }]);
+        assert_eq!(eight.labels[Point { x: 1, y: 0 }], None);
+    }
+}
@@ -0,0 +1,111 @@
+//! Tracing of mask region boundaries.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::{Point, Rect, Vector};
+
+/// A closed polyline on cell corners; the last point connects to the first one.
+///
+/// Only corners are kept: consecutive segments always turn.
+/// Outer boundaries go clockwise on screen (the `y` axis points down), holes counterclockwise,
+/// so the set cells are always on the right side.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Contour {
+    pub points: Vec<Point>,
+    pub is_hole: bool,
+}
+
+const STEPS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// Traces the boundaries of the `bounds` regions where `mask` is true; points out of `bounds` are unset.
+///
+/// Regions are 4-connected: diagonally touching cells get separate contours.
+pub fn trace_contours(bounds: Rect, mut mask: impl FnMut(Point) -> bool) -> Vec<Contour> {
+    let w = bounds.w() as u16 as usize;
+    let h = bounds.h() as u16 as usize;
+    if w == 0 || h == 0 { return Vec::new(); }
+    let cells: Vec<bool> = bounds.points().map(&mut mask).collect();
+    let set = |x: isize, y: isize| x >= 0 && y >= 0 && (x as usize) < w && (y as usize) < h && cells[y as usize * w + x as usize];
+    let vw = w + 1;
+    // Outgoing boundary edge directions per cell corner, as a bit mask over `STEPS`.
+    let mut out = vec![0u8; vw * (h + 1)];
+    for y in 0 .. h {
+        for x in 0 .. w {
+            let (xi, yi) = (x as isize, y as isize);
+            if !set(xi, yi) { continue; }
+            if !set(xi, yi - 1) { out[y * vw + x] |= 1 << 0; }
+            if !set(xi + 1, yi) { out[y * vw + x + 1] |= 1 << 1; }
+            if !set(xi, yi + 1) { out[(y + 1) * vw + x + 1] |= 1 << 2; }
+            if !set(xi - 1, yi) { out[(y + 1) * vw + x] |= 1 << 3; }
+        }
+    }
+    let corner = |v: usize| bounds.tl.offset(Vector { x: (v % vw) as u16 as i16, y: (v / vw) as u16 as i16 });
+    let mut contours = Vec::new();
+    let mut scan = 0;
+    while let Some(start) = (scan .. out.len()).find(|&v| out[v] != 0) {
+        scan = start;
+        // The first remaining corner is the top left one of its loop, so the loop turns there.
+        let first = out[start].trailing_zeros() as usize;
+        let mut points = vec![corner(start)];
+        let (mut v, mut d) = (start, first);
+        loop {
+            out[v] &= !(1 << d);
+            let (dx, dy) = STEPS[d];
+            v = (v as isize + dy as isize * vw as isize + dx as isize) as usize;
+            let available = out[v] | if v == start { 1 << first } else { 0 };
+            // Turning right first keeps diagonally touching regions apart.
+            let next = [(d + 1) % 4, d, (d + 3) % 4].into_iter().find(|&n| available & (1 << n) != 0).unwrap();
+            if v == start && next == first { break; }
+            if next != d { points.push(corner(v)); }
+            d = next;
+        }
+        contours.push(Contour { points, is_hole: first != 0 });
+    }
+    contours
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use quickcheck_macros::quickcheck;
+    use crate::*;
+    use crate::contour::*;
+    use crate::grid::BitGrid;
+
+    fn doubled_area(base: Point, points: &[Point]) -> i64 {
+        (0 .. points.len()).map(|i| {
+            let a = points[i].offset_from(base);
+            let b = points[(i + 1) % points.len()].offset_from(base);
+            a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64
+        }).sum()
+    }
+
+    #[quickcheck]
+    fn contours_enclose_set_cells(mask: BitGrid) -> bool {
+        let tl = mask.bounds().tl;
+        let contours = trace_contours(mask.bounds(), |p| mask.get(p));
+        let area: i64 = contours.iter().map(|c| doubled_area(tl, &c.points)).sum();
+        area == 2 * mask.count_ones() as i64 && contours.iter().all(|c| {
+            let n = c.points.len();
+            n >= 4 && n % 2 == 0 && (doubled_area(tl, &c.points) < 0) == c.is_hole && (0 .. n).all(|i| {
+                let (a, b, c) = (c.points[i], c.points[(i + 1) % n], c.points[(i + 2) % n]);
+                let (u, v) = (b.offset_from(a), c.offset_from(b));
+                (u.x == 0) != (u.y == 0) && (v.x == 0) != (v.y == 0) && (u.x == 0) != (v.x == 0)
+            })
+        })
+    }
+
+    #[test]
+    fn ring_and_diagonal() {
+        let p = |x, y| Point { x, y };
+        let bounds = Rect { tl: p(1, 1), size: Vector { x: 3, y: 3 } };
+        let ring = trace_contours(bounds, |q| q != p(2, 2));
+        assert_eq!(ring, [
+            Contour { points: vec![p(1, 1), p(4, 1), p(4, 4), p(1, 4)], is_hole: false },
+            Contour { points: vec![p(2, 2), p(2, 3), p(3, 3), p(3, 2)], is_hole: true },
+        ]);
+        let diagonal = trace_contours(bounds, |q| q == p(1, 1) || q == p(2, 2));
+        assert_eq!(diagonal.len(), 2);
+        assert!(diagonal.iter().all(|c| c.points.len() == 4 && !c.is_hole));
+    }
+}
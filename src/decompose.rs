@@ -0,0 +1,195 @@
+//! Decomposition of a cell mask into non-overlapping rects.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem::swap;
+use enum_derive_2018::{EnumDisplay, EnumFromStr};
+use macro_attr_2018::macro_attr;
+use crate::{Point, Rect, Vector};
+
+macro_attr! {
+    /// `Greedy` merges equal row spans of consecutive rows in a single pass.
+    /// `NearOptimal` repeatedly cuts out the largest rect, and falls back to the greedy
+    /// row or column merging if it gives fewer rects; it is quadratic in the worst case.
+    #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
+    #[derive(EnumDisplay!, EnumFromStr!)]
+    pub enum Decomposition { Greedy, NearOptimal }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Area {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+struct Mask {
+    w: u32,
+    h: u32,
+    cells: Vec<bool>,
+}
+
+impl Mask {
+    fn get(&self, x: u32, y: u32) -> bool { self.cells[(y * self.w + x) as usize] }
+
+    fn transpose(&self) -> Mask {
+        let cells = (0 .. self.w).flat_map(|x| (0 .. self.h).map(move |y| (x, y))).map(|(x, y)| self.get(x, y)).collect();
+        Mask { w: self.h, h: self.w, cells }
+    }
+
+    fn spans(&self, y: u32) -> Vec<(u32, u32)> {
+        let mut spans = Vec::new();
+        let mut x = 0;
+        while x < self.w {
+            if self.get(x, y) {
+                let start = x;
+                while x < self.w && self.get(x, y) { x += 1; }
+                spans.push((start, x));
+            } else {
+                x += 1;
+            }
+        }
+        spans
+    }
+
+    fn greedy(&self) -> Vec<Area> {
+        let mut areas = Vec::new();
+        let mut active: Vec<(u32, u32, u32)> = Vec::new();
+        let mut next = Vec::new();
+        for y in 0 ..= self.h {
+            let spans = if y == self.h { Vec::new() } else { self.spans(y) };
+            let mut i = 0;
+            for (l, r) in spans {
+                while i < active.len() && active[i].0 < l {
+                    let (x, end, t) = active[i];
+                    areas.push(Area { x, y: t, w: end - x, h: y - t });
+                    i += 1;
+                }
+                if i < active.len() && active[i].0 == l && active[i].1 == r {
+                    next.push(active[i]);
+                    i += 1;
+                } else {
+                    next.push((l, r, y));
+                }
+            }
+            for &(x, end, t) in &active[i ..] {
+                areas.push(Area { x, y: t, w: end - x, h: y - t });
+            }
+            swap(&mut active, &mut next);
+            next.clear();
+        }
+        areas
+    }
+
+    /// The largest rect of set cells, found row by row with the histogram stack method.
+    fn largest(&self) -> Option<Area> {
+        let mut heights = vec![0u32; self.w as usize];
+        let mut stack: Vec<(u32, u32)> = Vec::new();
+        let mut best: Option<Area> = None;
+        for y in 0 .. self.h {
+            for (x, height) in heights.iter_mut().enumerate() {
+                *height = if self.get(x as u32, y) { *height + 1 } else { 0 };
+            }
+            stack.clear();
+            for x in 0 ..= self.w {
+                let height = if x == self.w { 0 } else { heights[x as usize] };
+                let mut start = x;
+                while let Some(&(s, h)) = stack.last() {
+                    if h < height { break; }
+                    stack.pop();
+                    let area = Area { x: s, y: y + 1 - h, w: x - s, h };
+                    if h != 0 && best.is_none_or(|b| area.w * area.h > b.w * b.h) {
+                        best = Some(area);
+                    }
+                    start = s;
+                }
+                stack.push((start, height));
+            }
+        }
+        best
+    }
+
+    fn largest_first(mut self) -> Vec<Area> {
+        let mut areas = Vec::new();
+        while let Some(area) = self.largest() {
+            for y in area.y .. area.y + area.h {
+                for x in area.x .. area.x + area.w {
+                    self.cells[(y * self.w + x) as usize] = false;
+                }
+            }
+            areas.push(area);
+        }
+        areas
+    }
+}
+
+/// Returns non-overlapping rects covering exactly the `bounds` points where `mask` is true.
+///
+/// Rects are in no particular order.
+pub fn decompose(bounds: Rect, mask: impl FnMut(Point) -> bool, mode: Decomposition) -> Vec<Rect> {
+    let mask = Mask {
+        w: bounds.w() as u16 as u32,
+        h: bounds.h() as u16 as u32,
+        cells: if bounds.is_empty() { Vec::new() } else { bounds.points().map(mask).collect() },
+    };
+    let areas = match mode {
+        Decomposition::Greedy => mask.greedy(),
+        Decomposition::NearOptimal => {
+            let rows = mask.greedy();
+            let columns: Vec<_> = mask.transpose().greedy().into_iter()
+                .map(|a| Area { x: a.y, y: a.x, w: a.h, h: a.w })
+                .collect();
+            let largest = mask.largest_first();
+            [rows, columns, largest].into_iter().min_by_key(|x| x.len()).unwrap()
+        },
+    };
+    areas.into_iter().map(|a| Rect {
+        tl: bounds.tl.offset(Vector { x: a.x as u16 as i16, y: a.y as u16 as i16 }),
+        size: Vector { x: a.w as u16 as i16, y: a.h as u16 as i16 }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use crate::*;
+    use crate::decompose::*;
+    use crate::grid::BitGrid;
+
+    fn exact_cover(mask: &BitGrid, rects: &[Rect]) -> bool {
+        let mut covered = BitGrid::new(mask.bounds());
+        for r in rects {
+            if r.is_empty() { return false; }
+            for p in r.points() {
+                if !mask.bounds().contains(p) || covered.get(p) || !mask.get(p) { return false; }
+                covered.set(p, true);
+            }
+        }
+        covered.count_ones() == mask.count_ones()
+    }
+
+    #[quickcheck]
+    fn decomposition_is_exact_cover(mask: BitGrid) -> bool {
+        let greedy = decompose(mask.bounds(), |p| mask.get(p), Decomposition::Greedy);
+        let near = decompose(mask.bounds(), |p| mask.get(p), Decomposition::NearOptimal);
+        exact_cover(&mask, &greedy) && exact_cover(&mask, &near) && near.len() <= greedy.len()
+    }
+
+    #[test]
+    fn decompose_cross() {
+        let bounds = Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: 3, y: 3 } };
+        let cross = |p: Point| p.x == 1 || p.y == 1;
+        assert_eq!(decompose(bounds, cross, Decomposition::Greedy).len(), 3);
+        assert_eq!(decompose(bounds, cross, Decomposition::NearOptimal).len(), 3);
+        let stairs = Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: 4, y: 2 } };
+        let stairs_mask = |p: Point| p.x >= 1 - p.y;
+        assert_eq!(decompose(stairs, stairs_mask, Decomposition::Greedy).len(), 2);
+        let tall = Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: 2, y: 4 } };
+        let tall_mask = |p: Point| p.y != 0 || p.x == 0;
+        assert_eq!(decompose(tall, tall_mask, Decomposition::Greedy).len(), 2);
+        let comb = |p: Point| p.x == 0 || p.y % 2 == 0;
+        assert_eq!(decompose(tall, comb, Decomposition::Greedy).len(), 4);
+        assert_eq!(decompose(tall, comb, Decomposition::NearOptimal).len(), 3);
+    }
+}
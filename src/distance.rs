@@ -0,0 +1,189 @@
+//! Exact distance transforms.
+//!
+//! Uses the separable algorithm by Meijster, Roerdink and Hesselink: a column pass finds
+//! the nearest seed in every column, a row pass takes the lower envelope of the column distances.
+
+use alloc::vec::Vec;
+use enum_derive_2018::{EnumDisplay, EnumFromStr};
+use macro_attr_2018::macro_attr;
+use crate::{Point, Rect, Vector};
+use crate::grid::DenseGrid;
+
+macro_attr! {
+    #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
+    #[derive(EnumDisplay!, EnumFromStr!)]
+    pub enum Metric { Manhattan, Chebyshev, SquaredEuclidean }
+}
+
+impl Metric {
+    /// The distance between the row points `x` and `i`, the latter having the column distance `g`.
+    fn f(self, x: i64, i: i64, g: i64) -> i64 {
+        match self {
+            Metric::Manhattan => (x - i).abs() + g,
+            Metric::Chebyshev => (x - i).abs().max(g),
+            Metric::SquaredEuclidean => (x - i) * (x - i) + g * g,
+        }
+    }
+
+    /// The first row point `x > i` closer to `u` than to `i`, `i < u`.
+    fn sep(self, i: i64, u: i64, gi: i64, gu: i64, inf: i64) -> i64 {
+        match self {
+            Metric::Manhattan => {
+                if gu >= gi + u - i { return inf; }
+                if gi > gu + u - i { return -inf; }
+                (gu - gi + u + i).div_euclid(2)
+            },
+            Metric::Chebyshev => {
+                if gi <= gu {
+                    (i + gu).max((i + u).div_euclid(2))
+                } else {
+                    (u - gi).min((i + u).div_euclid(2))
+                }
+            },
+            Metric::SquaredEuclidean => (u * u - i * i + gu * gu - gi * gi).div_euclid(2 * (u - i)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct DistanceMap {
+    /// The distance from every point to the nearest seed.
+    pub distances: DenseGrid<u64>,
+    /// The nearest seed for every point, i.e. a discrete Voronoi diagram. Ties are broken arbitrarily.
+    pub nearest: Option<DenseGrid<Point>>,
+}
+
+/// Computes distances from the `bounds` points where `seeds` is true to all `bounds` points.
+///
+/// Returns `None` if there are no seeds.
+pub fn distance_transform(
+    bounds: Rect,
+    mut seeds: impl FnMut(Point) -> bool,
+    metric: Metric,
+    nearest: bool
+) -> Option<DistanceMap> {
+    let w = bounds.w() as u16 as usize;
+    let h = bounds.h() as u16 as usize;
+    let is_seed: Vec<bool> = if bounds.is_empty() { Vec::new() } else { bounds.points().map(&mut seeds).collect() };
+    if !is_seed.contains(&true) { return None; }
+    let inf = (w + h) as i64;
+    // The distance to and the row of the nearest seed in the same column.
+    let mut g = Vec::from_iter(is_seed.iter().map(|&s| if s { 0 } else { inf }));
+    let mut g_row: Vec<usize> = (0 .. w * h).map(|i| i / w).collect();
+    for x in 0 .. w {
+        for y in 1 .. h {
+            let (i, prev) = (y * w + x, (y - 1) * w + x);
+            if g[prev] + 1 < g[i] {
+                g[i] = g[prev] + 1;
+                g_row[i] = g_row[prev];
+            }
+        }
+        for y in (0 .. h.saturating_sub(1)).rev() {
+            let (i, next) = (y * w + x, (y + 1) * w + x);
+            if g[next] + 1 < g[i] {
+                g[i] = g[next] + 1;
+                g_row[i] = g_row[next];
+            }
+        }
+    }
+    let mut distances = Vec::with_capacity(w * h);
+    let mut nearest_seeds = Vec::with_capacity(if nearest { w * h } else { 0 });
+    let mut s = Vec::with_capacity(w);
+    let mut t = Vec::with_capacity(w);
+    let mut row = Vec::with_capacity(w);
+    for y in 0 .. h {
+        let g = |x: usize| g[y * w + x];
+        s.clear();
+        t.clear();
+        s.push(0);
+        t.push(0);
+        for u in 1 .. w {
+            while let (Some(&sq), Some(&tq)) = (s.last(), t.last()) {
+                if metric.f(tq, sq as i64, g(sq)) <= metric.f(tq, u as i64, g(u)) { break; }
+                s.pop();
+                t.pop();
+            }
+            if let Some(&sq) = s.last() {
+                let sep = 1 + metric.sep(sq as i64, u as i64, g(sq), g(u), inf);
+                if sep < w as i64 {
+                    s.push(u);
+                    t.push(sep);
+                }
+            } else {
+                s.push(u);
+                t.push(0);
+            }
+        }
+        row.clear();
+        for u in (0 .. w).rev() {
+            let sq = *s.last().unwrap();
+            row.push((metric.f(u as i64, sq as i64, g(sq)) as u64, sq));
+            if u as i64 == *t.last().unwrap() {
+                s.pop();
+                t.pop();
+            }
+        }
+        for &(d, sx) in row.iter().rev() {
+            distances.push(d);
+            if nearest {
+                let sy = g_row[y * w + sx];
+                nearest_seeds.push(bounds.tl.offset(Vector { x: sx as u16 as i16, y: sy as u16 as i16 }));
+            }
+        }
+    }
+    let mut distances = distances.into_iter();
+    let mut nearest_seeds = nearest_seeds.into_iter();
+    Some(DistanceMap {
+        distances: DenseGrid::from_fn(bounds, |_| distances.next().unwrap()),
+        nearest: if nearest { Some(DenseGrid::from_fn(bounds, |_| nearest_seeds.next().unwrap())) } else { None },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use quickcheck_macros::quickcheck;
+    use crate::*;
+    use crate::distance::*;
+    use crate::grid::BitGrid;
+
+    fn distance(metric: Metric, a: Point, b: Point) -> u64 {
+        let d = a.offset_from(b);
+        let (dx, dy) = ((d.x as i64).unsigned_abs(), (d.y as i64).unsigned_abs());
+        match metric {
+            Metric::Manhattan => dx + dy,
+            Metric::Chebyshev => dx.max(dy),
+            Metric::SquaredEuclidean => dx * dx + dy * dy,
+        }
+    }
+
+    #[quickcheck]
+    fn distance_transform_is_exact(mask: BitGrid) -> bool {
+        let bounds = mask.bounds();
+        let is_seed = |p: Point| mask.get(p);
+        let seeds: Vec<_> = mask.points().collect();
+        [Metric::Manhattan, Metric::Chebyshev, Metric::SquaredEuclidean].into_iter().all(|metric| {
+            let Some(map) = distance_transform(bounds, is_seed, metric, true) else { return seeds.is_empty(); };
+            let nearest = map.nearest.unwrap();
+            bounds.points().all(|p| {
+                let expected = seeds.iter().map(|&s| distance(metric, p, s)).min().unwrap();
+                map.distances[p] == expected && is_seed(nearest[p]) && distance(metric, p, nearest[p]) == expected
+            })
+        })
+    }
+
+    #[test]
+    fn voronoi() {
+        let bounds = Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: 7, y: 3 } };
+        let a = Point { x: 0, y: 1 };
+        let b = Point { x: 6, y: 0 };
+        let map = distance_transform(bounds, |p| p == a || p == b, Metric::Chebyshev, true).unwrap();
+        assert_eq!(map.distances[Point { x: 2, y: 2 }], 2);
+        assert_eq!(map.nearest.as_ref().unwrap()[Point { x: 2, y: 2 }], a);
+        assert_eq!(map.nearest.as_ref().unwrap()[Point { x: 5, y: 2 }], b);
+        let map = distance_transform(bounds, |p| p == a, Metric::SquaredEuclidean, false).unwrap();
+        assert_eq!(map.distances[Point { x: 6, y: 2 }], 37);
+        assert!(map.nearest.is_none());
+        assert!(distance_transform(bounds, |_| false, Metric::Manhattan, false).is_none());
+    }
+}
@@ -0,0 +1,694 @@
+//! Grids of cells addressed by [`Point`].
+
+#[cfg(feature="alloc")]
+use alloc::collections::BTreeMap;
+#[cfg(feature="alloc")]
+use alloc::vec;
+#[cfg(feature="alloc")]
+use alloc::vec::Vec;
+#[cfg(feature="alloc")]
+use core::cmp::{min, max};
+#[cfg(feature="alloc")]
+use core::iter::repeat_with;
+#[cfg(feature="alloc")]
+use core::iter;
+#[cfg(feature="alloc")]
+use core::ops::{Index, IndexMut, BitAnd, BitOr, BitXor, Not};
+#[cfg(feature="alloc")]
+use either::Right;
+use crate::{Point, Rect};
+#[cfg(feature="alloc")]
+use crate::{Range1d, Span1d, Span2d, Vector};
+#[cfg(all(test, feature="alloc"))]
+use quickcheck::{Arbitrary, Gen};
+
+/// Cell access shared by dense and sparse grids, so that algorithms can run on either.
+pub trait Grid {
+    type Item;
+
+    /// A rect containing all present cells.
+    fn bounds(&self) -> Rect;
+
+    fn get(&self, p: Point) -> Option<&Self::Item>;
+
+    fn get_mut(&mut self, p: Point) -> Option<&mut Self::Item>;
+
+    /// Stores the cell value, returning the previous one.
+    ///
+    /// # Panics
+    ///
+    /// A grid with fixed bounds panics if `p` is out of them.
+    fn set(&mut self, p: Point, value: Self::Item) -> Option<Self::Item>;
+}
+
+/// A grid with a value for every point of its bounds.
+#[cfg(feature="alloc")]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct DenseGrid<T> {
+    bounds: Rect,
+    cells: Vec<T>,
+}
+
+#[cfg(feature="alloc")]
+impl<T> DenseGrid<T> {
+    pub fn from_fn(bounds: Rect, f: impl FnMut(Point) -> T) -> Self {
+        DenseGrid { bounds, cells: bounds.points().map(f).collect() }
+    }
+
+    fn index(&self, p: Point) -> Option<usize> {
+        let d = p.offset_from(self.bounds.tl);
+        let (x, y) = (d.x as u16 as usize, d.y as u16 as usize);
+        let w = self.bounds.w() as u16 as usize;
+        if x < w && y < self.bounds.h() as u16 as usize { Some(y * w + x) } else { None }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(Point, &T)> + '_ {
+        self.bounds.points().zip(self.cells.iter())
+    }
+
+    /// Sets all cells in the `rect` and bounds intersection.
+    pub fn fill(&mut self, rect: Rect, value: T) where T: Clone {
+        for p in rect.intersect(self.bounds).points() {
+            let index = self.index(p).unwrap();
+            self.cells[index] = value.clone();
+        }
+    }
+}
+
+#[cfg(feature="alloc")]
+impl<T: Clone> DenseGrid<T> {
+    pub fn new(bounds: Rect, value: T) -> Self {
+        DenseGrid { bounds, cells: repeat_with(|| value.clone()).take(bounds.area() as usize).collect() }
+    }
+}
+
+#[cfg(feature="alloc")]
+impl<T> Grid for DenseGrid<T> {
+    type Item = T;
+
+    fn bounds(&self) -> Rect { self.bounds }
+
+    fn get(&self, p: Point) -> Option<&T> { self.index(p).map(|i| &self.cells[i]) }
+
+    fn get_mut(&mut self, p: Point) -> Option<&mut T> { self.index(p).map(|i| &mut self.cells[i]) }
+
+    fn set(&mut self, p: Point, value: T) -> Option<T> {
+        let index = self.index(p).expect("point out of grid bounds");
+        Some(core::mem::replace(&mut self.cells[index], value))
+    }
+}
+
+#[cfg(feature="alloc")]
+impl<T> Index<Point> for DenseGrid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T { self.get(p).expect("point out of grid bounds") }
+}
+
+#[cfg(feature="alloc")]
+impl<T> IndexMut<Point> for DenseGrid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T { self.get_mut(p).expect("point out of grid bounds") }
+}
+
+#[cfg(feature="alloc")]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+struct Chunk<T> {
+    cells: Vec<Option<T>>,
+    len: usize,
+}
+
+#[cfg(feature="alloc")]
+impl<T> Chunk<T> {
+    fn new(area: u32) -> Self {
+        Chunk { cells: repeat_with(|| None).take(area as usize).collect(), len: 0 }
+    }
+
+    fn replace(&mut self, index: usize, value: Option<T>) -> Option<T> {
+        let old = core::mem::replace(&mut self.cells[index], value);
+        self.len = self.len + self.cells[index].is_some() as usize - old.is_some() as usize;
+        old
+    }
+}
+
+#[cfg(feature="alloc")]
+const CHUNK_ORIGIN: Point = Point { x: 0, y: 0 };
+
+/// A grid of optional values over the whole plane, stored in `tile` sized chunks
+/// allocated only where there are values.
+///
+/// Chunks are aligned to multiples of the tile size, see [`Point::chunk`].
+#[cfg(feature="alloc")]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SparseGrid<T> {
+    tile: Vector,
+    chunks: BTreeMap<(i16, i16), Chunk<T>>,
+}
+
+#[cfg(feature="alloc")]
+impl<T> SparseGrid<T> {
+    /// `tile` components are interpreted as `u16`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any `tile` component is zero.
+    pub fn new(tile: Vector) -> Self {
+        assert!(tile.x != 0 && tile.y != 0, "zero tile size");
+        SparseGrid { tile, chunks: BTreeMap::new() }
+    }
+
+    pub fn tile(&self) -> Vector { self.tile }
+
+    fn key(&self, p: Point) -> (i16, i16) {
+        let chunk = p.chunk(self.tile, CHUNK_ORIGIN);
+        (chunk.y, chunk.x)
+    }
+
+    fn cell_index(tile: Vector, p: Point) -> usize {
+        let (w, h) = (tile.x as u16 as i32, tile.y as u16 as i32);
+        ((p.y as i32).rem_euclid(h) * w + (p.x as i32).rem_euclid(w)) as usize
+    }
+
+    fn chunk_tl(&self, key: (i16, i16)) -> (i32, i32) {
+        (key.1 as i32 * self.tile.x as u16 as i32, key.0 as i32 * self.tile.y as u16 as i32)
+    }
+
+    /// The number of present cells.
+    pub fn len(&self) -> usize { self.chunks.values().map(|c| c.len).sum() }
+
+    pub fn is_empty(&self) -> bool { self.chunks.is_empty() }
+
+    pub fn remove(&mut self, p: Point) -> Option<T> {
+        let key = self.key(p);
+        let chunk = self.chunks.get_mut(&key)?;
+        let old = chunk.replace(Self::cell_index(self.tile, p), None);
+        if chunk.len == 0 {
+            self.chunks.remove(&key);
+        }
+        old
+    }
+
+    fn update(&mut self, rect: Rect, mut value: impl FnMut() -> Option<T>, allocate: bool) {
+        let tile_size = self.tile;
+        let area = tile_size.rect_area();
+        for tile in rect.tiles_aligned(tile_size, CHUNK_ORIGIN) {
+            let key = self.key(tile.rect.tl);
+            let in_chunk = |p: &Point| p.chunk(tile_size, CHUNK_ORIGIN) == Point { x: key.1, y: key.0 };
+            let chunk = if allocate {
+                Some(self.chunks.entry(key).or_insert_with(|| Chunk::new(area)))
+            } else {
+                self.chunks.get_mut(&key)
+            };
+            if let Some(chunk) = chunk {
+                for p in tile.rect.points().filter(in_chunk) {
+                    chunk.replace(Self::cell_index(tile_size, p), value());
+                }
+                if chunk.len == 0 {
+                    self.chunks.remove(&key);
+                }
+            }
+            // Only possible for a rect wrapping around the plane.
+            for p in tile.rect.points().filter(|p| !in_chunk(p)) {
+                match value() {
+                    Some(value) => { self.set(p, value); },
+                    None => { self.remove(p); },
+                }
+            }
+        }
+    }
+
+    pub fn fill(&mut self, rect: Rect, value: T) where T: Clone {
+        self.update(rect, || Some(value.clone()), true);
+    }
+
+    pub fn clear(&mut self, rect: Rect) {
+        self.update(rect, || None, false);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(Point, &T)> + '_ {
+        self.chunks.iter().flat_map(move |(&key, chunk)| {
+            let (l, t) = self.chunk_tl(key);
+            let w = self.tile.x as u16 as i32;
+            chunk.cells.iter().enumerate().filter_map(move |(i, cell)| cell.as_ref().map(|value| {
+                let p = Point { x: (l + i as i32 % w) as i16, y: (t + i as i32 / w) as i16 };
+                (p, value)
+            }))
+        })
+    }
+
+    /// The exact bounds of the present cells, which can cover a whole axis unlike [`Grid::bounds`].
+    /// Returns `None` if the grid is empty.
+    pub fn span(&self) -> Option<Span2d> {
+        self.occupied_rects().map(|r| Span2d::try_from(r).expect("chunk wraps around")).reduce(Span2d::union)
+    }
+
+    /// Yields the bounding rect of the present cells in every allocated chunk.
+    pub fn occupied_rects(&self) -> impl Iterator<Item=Rect> + '_ {
+        self.chunks.iter().map(move |(&key, chunk)| {
+            let (l, t) = self.chunk_tl(key);
+            let w = self.tile.x as u16 as i32;
+            let (mut min_x, mut min_y, mut max_x, mut max_y) = (i32::MAX, i32::MAX, i32::MIN, i32::MIN);
+            for (i, _) in chunk.cells.iter().enumerate().filter(|x| x.1.is_some()) {
+                let (x, y) = (l + i as i32 % w, t + i as i32 / w);
+                min_x = min(min_x, x);
+                min_y = min(min_y, y);
+                max_x = max(max_x, x);
+                max_y = max(max_y, y);
+            }
+            Rect::from_tl_br(
+                Point { x: min_x as i16, y: min_y as i16 },
+                Point { x: (max_x + 1) as u16 as i16, y: (max_y + 1) as u16 as i16 }
+            )
+        })
+    }
+}
+
+#[cfg(feature="alloc")]
+impl<T> Grid for SparseGrid<T> {
+    type Item = T;
+
+    /// The bounding rect of the present cells, computed on every call.
+    ///
+    /// A rect cannot cover a whole axis, so if the present cells reach both ends of one,
+    /// the rect leaves out its last coordinate; [`SparseGrid::span`] is exact.
+    fn bounds(&self) -> Rect {
+        let Some(span) = self.span() else { return Rect { tl: CHUNK_ORIGIN, size: Vector::null() } };
+        let range = |s: Span1d| Range1d::new(s.start(), s.start().wrapping_add(min(s.len(), u16::MAX as u32) as u16 as i16));
+        Rect::from_h_v_ranges(range(span.h), range(span.v))
+    }
+
+    fn get(&self, p: Point) -> Option<&T> {
+        self.chunks.get(&self.key(p))?.cells[Self::cell_index(self.tile, p)].as_ref()
+    }
+
+    fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        let key = self.key(p);
+        self.chunks.get_mut(&key)?.cells[Self::cell_index(self.tile, p)].as_mut()
+    }
+
+    fn set(&mut self, p: Point, value: T) -> Option<T> {
+        let key = self.key(p);
+        let area = self.tile.rect_area();
+        let chunk = self.chunks.entry(key).or_insert_with(|| Chunk::new(area));
+        chunk.replace(Self::cell_index(self.tile, p), Some(value))
+    }
+}
+
+#[cfg(feature="alloc")]
+impl<T> Index<Point> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T { self.get(p).expect("vacant grid cell") }
+}
+
+#[cfg(feature="alloc")]
+impl<T> IndexMut<Point> for SparseGrid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T { self.get_mut(p).expect("vacant grid cell") }
+}
+
+/// A packed boolean mask over a bounding rect.
+///
+/// Points out of the bounds are unset. Binary operators (`&`, `|`, `^`) combine masks
+/// with different bounds: the result bounds are the bounds intersection for `&`,
+/// and the bounds union for `|` and `^`.
+#[cfg(feature="alloc")]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct BitGrid {
+    bounds: Rect,
+    stride: usize,
+    words: Vec<u64>,
+}
+
+#[cfg(feature="alloc")]
+impl BitGrid {
+    pub fn new(bounds: Rect) -> Self {
+        let stride = (bounds.w() as u16 as usize).div_ceil(64);
+        BitGrid { bounds, stride, words: vec![0; stride * bounds.h() as u16 as usize] }
+    }
+
+    pub fn from_fn(bounds: Rect, mut f: impl FnMut(Point) -> bool) -> Self {
+        let mut grid = BitGrid::new(bounds);
+        for p in bounds.points() {
+            if f(p) { grid.set(p, true); }
+        }
+        grid
+    }
+
+    pub fn bounds(&self) -> Rect { self.bounds }
+
+    /// The same mask moved by `d`.
+    pub fn offset(&self, d: Vector) -> BitGrid {
+        BitGrid { bounds: self.bounds.offset(d), ..self.clone() }
+    }
+
+    fn w(&self) -> u32 { self.bounds.w() as u16 as u32 }
+
+    fn h(&self) -> u32 { self.bounds.h() as u16 as u32 }
+
+    fn local(&self, p: Point) -> Option<(u32, u32)> {
+        let d = p.offset_from(self.bounds.tl);
+        let (x, y) = (d.x as u16 as u32, d.y as u16 as u32);
+        if x < self.w() && y < self.h() { Some((x, y)) } else { None }
+    }
+
+    pub fn get(&self, p: Point) -> bool {
+        self.local(p).is_some_and(|(x, y)| self.words[y as usize * self.stride + x as usize / 64] & (1 << (x % 64)) != 0)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `p` is out of the bounds.
+    pub fn set(&mut self, p: Point, value: bool) {
+        let (x, y) = self.local(p).expect("point out of mask bounds");
+        let word = &mut self.words[y as usize * self.stride + x as usize / 64];
+        if value { *word |= 1 << (x % 64); } else { *word &= !(1 << (x % 64)); }
+    }
+
+    /// Sets or unsets all points in the `rect` and bounds intersection.
+    pub fn fill(&mut self, rect: Rect, value: bool) {
+        let rect = rect.intersect(self.bounds);
+        if rect.is_empty() { return; }
+        let (l, t) = self.local(rect.tl).unwrap();
+        let r = l + rect.w() as u16 as u32;
+        for y in t .. t + rect.h() as u16 as u32 {
+            let row = y as usize * self.stride;
+            for k in l / 64 ..= (r - 1) / 64 {
+                let from = l.max(k * 64) - k * 64;
+                let to = r.min(k * 64 + 64) - k * 64;
+                let mask = (u64::MAX >> (64 - (to - from))) << from;
+                let word = &mut self.words[row + k as usize];
+                if value { *word |= mask; } else { *word &= !mask; }
+            }
+        }
+    }
+
+    pub fn count_ones(&self) -> u32 { self.words.iter().map(|w| w.count_ones()).sum() }
+
+    /// The same as `self | other`, but returns `None` instead of panicking
+    /// if the bounds union does not fit a rect.
+    pub fn checked_or(&self, other: &BitGrid) -> Option<BitGrid> {
+        Some(self.combine(other, self.union_bounds(other)?, |a, b| a | b))
+    }
+
+    /// The same as `self ^ other`, but returns `None` instead of panicking
+    /// if the bounds union does not fit a rect.
+    pub fn checked_xor(&self, other: &BitGrid) -> Option<BitGrid> {
+        Some(self.combine(other, self.union_bounds(other)?, |a, b| a ^ b))
+    }
+
+    /// 64 mask bits starting at the local `x` (which can be negative) in the local row `y`.
+    fn bits_at(&self, x: i32, y: i32) -> u64 {
+        if y < 0 || y >= self.h() as i32 { return 0; }
+        let row = &self.words[y as usize * self.stride .. (y as usize + 1) * self.stride];
+        let word = |k: i32| if k < 0 || k >= row.len() as i32 { 0 } else { row[k as usize] };
+        let (k, shift) = (x.div_euclid(64), x.rem_euclid(64));
+        if shift == 0 { word(k) } else { (word(k) >> shift) | (word(k + 1) << (64 - shift)) }
+    }
+
+    fn tail_mask(&self) -> u64 {
+        match self.w() % 64 {
+            0 => u64::MAX,
+            n => u64::MAX >> (64 - n),
+        }
+    }
+
+    fn combine(&self, other: &BitGrid, bounds: Rect, op: impl Fn(u64, u64) -> u64) -> BitGrid {
+        let mut res = BitGrid::new(bounds);
+        if res.words.is_empty() { return res; }
+        // The operand coordinates of the result top left corner, counted forward around the plane.
+        let offset = |g: &BitGrid| (
+            bounds.l().wrapping_sub(g.bounds.l()) as u16 as i32,
+            bounds.t().wrapping_sub(g.bounds.t()) as u16 as i32
+        );
+        let (ax, ay) = offset(self);
+        let (bx, by) = offset(other);
+        // Operand coordinates past the plane end continue from its start.
+        let read = |g: &BitGrid, x: i32, y: i32| {
+            let y = if y >= 1 << 16 { y - (1 << 16) } else { y };
+            g.bits_at(x, y) | g.bits_at(x - (1 << 16), y)
+        };
+        let tail = res.tail_mask();
+        for y in 0 .. res.h() as i32 {
+            for k in 0 .. res.stride {
+                let x = k as i32 * 64;
+                let mut word = op(read(self, ax + x, ay + y), read(other, bx + x, by + y));
+                if k == res.stride - 1 { word &= tail; }
+                res.words[y as usize * res.stride + k] = word;
+            }
+        }
+        res
+    }
+
+    fn union_bounds(&self, other: &BitGrid) -> Option<Rect> {
+        match self.bounds.union(other.bounds) {
+            Some(Right(rect)) => Some(rect),
+            _ => None,
+        }
+    }
+
+    /// The first local `x` not less than `from` where the row bit equals `value`.
+    fn find(&self, y: u32, from: u32, value: bool) -> Option<u32> {
+        let row = &self.words[y as usize * self.stride .. (y as usize + 1) * self.stride];
+        let load = |k: usize| row.get(k).map(|&w| if value { w } else { !w });
+        let mut k = from as usize / 64;
+        let mut word = load(k)? & (u64::MAX << (from % 64));
+        while word == 0 {
+            k += 1;
+            word = load(k)?;
+        }
+        let x = k as u32 * 64 + word.trailing_zeros();
+        if x < self.w() { Some(x) } else { None }
+    }
+
+    /// Yields maximal runs of set points in every row, top to bottom, left to right.
+    pub fn spans(&self) -> impl Iterator<Item=(i16, Range1d)> + '_ {
+        let (l, t) = (self.bounds.l(), self.bounds.t());
+        (0 .. self.h()).flat_map(move |y| {
+            let mut x = 0;
+            iter::from_fn(move || {
+                let start = self.find(y, x, true)?;
+                let end = self.find(y, start, false).unwrap_or(self.w());
+                x = end;
+                Some((
+                    t.wrapping_add(y as u16 as i16),
+                    Range1d::new(l.wrapping_add(start as u16 as i16), l.wrapping_add(end as u16 as i16))
+                ))
+            })
+        })
+    }
+
+    pub fn points(&self) -> impl Iterator<Item=Point> + '_ {
+        self.spans().flat_map(|(y, h)| h.map(move |x| Point { x, y }))
+    }
+
+    /// The bounding rect of the set points, or an empty rect at the bounds top left corner if there are none.
+    pub fn bounding_rect(&self) -> Rect {
+        let spans = self.spans().map(|(y, h)| {
+            let d = Point { x: h.start, y }.offset_from(self.bounds.tl);
+            let x = d.x as u16 as u32;
+            (x, d.y as u16 as u32, x + h.len() as u32)
+        });
+        let Some((l, t, r, b)) = spans.fold(None, |acc: Option<(u32, u32, u32, u32)>, (l, y, r)| Some(match acc {
+            None => (l, y, r, y + 1),
+            Some(acc) => (acc.0.min(l), acc.1, acc.2.max(r), y + 1),
+        })) else {
+            return Rect { tl: self.bounds.tl, size: Vector::null() };
+        };
+        Rect {
+            tl: self.bounds.tl.offset(Vector { x: l as u16 as i16, y: t as u16 as i16 }),
+            size: Vector { x: (r - l) as u16 as i16, y: (b - t) as u16 as i16 }
+        }
+    }
+}
+
+#[cfg(feature="alloc")]
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, other: &BitGrid) -> BitGrid {
+        self.combine(other, self.bounds.intersect(other.bounds), |a, b| a & b)
+    }
+}
+
+#[cfg(feature="alloc")]
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    /// # Panics
+    ///
+    /// Panics if the bounds union does not fit a rect, see [`BitGrid::checked_or`].
+    fn bitor(self, other: &BitGrid) -> BitGrid {
+        self.checked_or(other).expect("mask bounds union does not fit a rect")
+    }
+}
+
+#[cfg(feature="alloc")]
+impl BitXor for &BitGrid {
+    type Output = BitGrid;
+
+    /// # Panics
+    ///
+    /// Panics if the bounds union does not fit a rect, see [`BitGrid::checked_xor`].
+    fn bitxor(self, other: &BitGrid) -> BitGrid {
+        self.checked_xor(other).expect("mask bounds union does not fit a rect")
+    }
+}
+
+#[cfg(feature="alloc")]
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    /// Inverts the mask within its bounds.
+    fn not(self) -> BitGrid {
+        let empty = BitGrid::new(Rect { tl: self.bounds.tl, size: Vector::null() });
+        self.combine(&empty, self.bounds, |a, _| !a)
+    }
+}
+
+/// A mask of up to 12×12 cells with a random density, small enough to check mask algorithms by brute force.
+#[cfg(all(test, feature="alloc"))]
+impl Arbitrary for BitGrid {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let tl = Point::arbitrary(g);
+        let size = Vector { x: (u8::arbitrary(g) % 13) as i16, y: (u8::arbitrary(g) % 13) as i16 };
+        let density = u8::arbitrary(g);
+        BitGrid::from_fn(Rect { tl, size }, |_| u8::arbitrary(g) < density)
+    }
+}
+
+#[cfg(all(test, feature="alloc"))]
+mod tests {
+    use alloc::vec::Vec;
+    use quickcheck::TestResult;
+    use quickcheck_macros::quickcheck;
+    use crate::*;
+    use crate::grid::*;
+
+    #[quickcheck]
+    fn sparse_grid_agrees_with_dense(ops: Vec<(bool, Rect, u8)>, tile: (u8, u8)) -> TestResult {
+        let tile = Vector { x: tile.0 as i16 % 7 + 1, y: tile.1 as i16 % 7 + 1 };
+        let bounds = Rect { tl: Point { x: -20, y: -20 }, size: Vector { x: 40, y: 40 } };
+        let mut dense = DenseGrid::new(bounds, None);
+        let mut sparse = SparseGrid::new(tile);
+        for (fill, rect, value) in ops {
+            let rect = rect.intersect(bounds);
+            if fill {
+                dense.fill(rect, Some(value));
+                sparse.fill(rect, value);
+            } else {
+                dense.fill(rect, None);
+                sparse.clear(rect);
+            }
+        }
+        let present: Vec<_> = dense.iter().filter_map(|(p, v)| v.map(|v| (p, v))).collect();
+        let mut stored: Vec<_> = sparse.iter().map(|(p, &v)| (p, v)).collect();
+        stored.sort_by_key(|x| (x.0.y, x.0.x));
+        let sparse_bounds = sparse.bounds();
+        TestResult::from_bool(
+            present == stored && sparse.len() == present.len() &&
+            bounds.points().all(|p| dense[p] == sparse.get(p).copied()) &&
+            present.iter().all(|&(p, _)| sparse_bounds.contains(p)) &&
+            sparse.occupied_rects().all(|r| !r.is_empty() && sparse_bounds.intersect(r) == r)
+        )
+    }
+
+    fn small(r: Rect) -> Rect {
+        Rect { tl: Point { x: r.l() % 100, y: r.t() % 100 }, size: Vector { x: (r.w() as u16 % 150) as i16, y: (r.h() as u16 % 8) as i16 } }
+    }
+
+    #[quickcheck]
+    fn bit_grid_operations(a: Rect, b: Rect, fills: Vec<(Rect, bool)>, wrap: bool) -> bool {
+        let d = if wrap { Vector { x: i16::MAX - 50, y: i16::MAX - 3 } } else { Vector::null() };
+        let small = |r: Rect| Rect { tl: small(r).tl.offset(d), size: small(r).size };
+        let (a, b) = (small(a), small(b));
+        let mut x = BitGrid::new(a);
+        let mut y = BitGrid::new(b);
+        let mut dense_x = DenseGrid::new(a, false);
+        let mut dense_y = DenseGrid::new(b, false);
+        for (i, (r, value)) in fills.into_iter().enumerate() {
+            let r = small(r);
+            if i % 2 == 0 {
+                x.fill(r, value);
+                dense_x.fill(r, value);
+            } else {
+                y.fill(r, value);
+                dense_y.fill(r, value);
+            }
+        }
+        let get = |g: &DenseGrid<bool>, p: Point| g.get(p).copied().unwrap_or(false);
+        let and = &x & &y;
+        let or = &x | &y;
+        let xor = &x ^ &y;
+        let not = !&x;
+        let set: Vec<_> = x.points().collect();
+        let expected: Vec<_> = dense_x.iter().filter(|x| *x.1).map(|x| x.0).collect();
+        let bounding = x.bounding_rect();
+        and.bounds() == a.intersect(b) &&
+        a.points().all(|p| x.get(p) == get(&dense_x, p) && not.get(p) != x.get(p)) &&
+        and.bounds().points().all(|p| and.get(p) == (get(&dense_x, p) && get(&dense_y, p))) &&
+        or.bounds().points().all(|p| or.get(p) == (get(&dense_x, p) || get(&dense_y, p))) &&
+        xor.bounds().points().all(|p| xor.get(p) == (get(&dense_x, p) != get(&dense_y, p))) &&
+        set == expected && x.count_ones() as usize == set.len() &&
+        set.iter().all(|&p| bounding.contains(p)) &&
+        (set.is_empty() || (
+            set.iter().any(|p| p.x == bounding.l()) && set.iter().any(|p| p.y == bounding.t()) &&
+            set.iter().any(|p| p.x == bounding.r_inner()) && set.iter().any(|p| p.y == bounding.b_inner())
+        ))
+    }
+
+    #[test]
+    fn bit_grid() {
+        let mut grid = BitGrid::new(Rect { tl: Point { x: -10, y: 0 }, size: Vector { x: 100, y: 3 } });
+        grid.fill(Rect { tl: Point { x: 50, y: 1 }, size: Vector { x: 30, y: 5 } }, true);
+        grid.set(Point { x: 60, y: 1 }, false);
+        assert_eq!(grid.count_ones(), 59);
+        assert_eq!(grid.spans().next(), Some((1, Range1d::new(50, 60))));
+        assert_eq!(grid.spans().count(), 3);
+        assert_eq!(grid.bounding_rect(), Rect { tl: Point { x: 50, y: 1 }, size: Vector { x: 30, y: 2 } });
+        assert!(!grid.get(Point { x: 200, y: 1 }));
+        let mut a = BitGrid::new(Rect { tl: Point { x: 32700, y: 0 }, size: Vector { x: 100, y: 1 } });
+        let mut b = BitGrid::new(Rect { tl: Point { x: -32700, y: 0 }, size: Vector { x: 10, y: 1 } });
+        a.fill(a.bounds(), true);
+        b.fill(b.bounds(), true);
+        let or = &a | &b;
+        assert_eq!(or.count_ones(), 110);
+        assert!(or.get(Point { x: -32695, y: 0 }));
+        assert_eq!((&or & &b).count_ones(), 10);
+        // The bounds wrap around each other, so they overlap at both ends.
+        let wide = |l: i16| {
+            let mut grid = BitGrid::new(Rect { tl: Point { x: l, y: 0 }, size: Vector { x: 40000u16 as i16, y: 1 } });
+            grid.fill(grid.bounds(), true);
+            grid
+        };
+        let (c, d) = (wide(20000), wide(-10000));
+        let and = &c & &d;
+        assert_eq!(and.count_ones(), 10000 + 4464);
+        assert!(and.get(Point { x: -8000, y: 0 }) && and.get(Point { x: 25000, y: 0 }) && !and.get(Point { x: 0, y: 0 }));
+        assert_eq!(c.checked_or(&d), None);
+        assert_eq!(c.checked_xor(&d), None);
+        assert_eq!(a.checked_or(&b), Some(or));
+    }
+
+    #[test]
+    fn sparse_grid() {
+        let mut grid = SparseGrid::new(Vector { x: 16, y: 16 });
+        assert_eq!(grid.set(Point { x: -1000, y: 30000 }, 1), None);
+        assert_eq!(grid.set(Point { x: -1000, y: 30000 }, 2), Some(1));
+        grid.fill(Rect { tl: Point { x: 100, y: -5 }, size: Vector { x: 40, y: 10 } }, 3);
+        assert_eq!(grid.len(), 401);
+        assert_eq!(grid[Point { x: 139, y: 4 }], 3);
+        assert_eq!(grid.get(Point { x: 140, y: 4 }), None);
+        assert_eq!(grid.bounds(), Rect::from_tl_br(Point { x: -1000, y: -5 }, Point { x: 140, y: 30001 }));
+        grid.clear(Rect { tl: Point { x: 90, y: -10 }, size: Vector { x: 60, y: 20 } });
+        assert_eq!(grid.remove(Point { x: -1000, y: 30000 }), Some(2));
+        assert!(grid.is_empty());
+        let mut wide = SparseGrid::new(Vector { x: 40000u16 as i16, y: 1 });
+        wide.set(Point { x: 0, y: 0 }, ());
+        wide.set(Point { x: i16::MAX, y: 0 }, ());
+        wide.set(Point { x: -5, y: 0 }, ());
+        assert_eq!(wide.bounds(), Rect { tl: Point { x: -5, y: 0 }, size: Vector { x: 32773u16 as i16, y: 1 } });
+        wide.set(Point { x: i16::MIN, y: 0 }, ());
+        assert_eq!(wide.span().map(|x| x.h), Some(Span1d::full()));
+        assert_eq!(wide.bounds(), Rect { tl: Point { x: i16::MIN, y: 0 }, size: Vector { x: -1, y: 1 } });
+        assert_eq!(SparseGrid::<()>::new(Vector { x: 4, y: 4 }).span(), None);
+    }
+}
@@ -0,0 +1,684 @@
+//! Two-phase (measure/arrange) layout protocol.
+//!
+//! Sizes are treated as unsigned: a `Vector` component is interpreted as `u16`,
+//! and `u16::MAX` (i.e. [`UNBOUNDED`]) passed as available size means "no limit".
+
+use core::cmp::{min, max};
+use core::ops::DerefMut;
+use enum_derive_2018::{EnumDisplay, EnumFromStr};
+use macro_attr_2018::macro_attr;
+use crate::{HAlign, Point, Rect, Side, Thickness, VAlign, Vector};
+
+pub const UNBOUNDED: i16 = u16::MAX as i16;
+
+pub trait Layout {
+    /// Returns the desired size, which is not necessary fits into `available`.
+    fn measure(&mut self, available: Vector) -> Vector;
+
+    /// Returns the rect actually occupied.
+    fn arrange(&mut self, final_rect: Rect) -> Rect;
+}
+
+impl<T: Layout + ?Sized> Layout for &mut T {
+    fn measure(&mut self, available: Vector) -> Vector { (**self).measure(available) }
+
+    fn arrange(&mut self, final_rect: Rect) -> Rect { (**self).arrange(final_rect) }
+}
+
+fn add(a: i16, b: i16) -> i16 { (a as u16).saturating_add(b as u16) as i16 }
+
+fn sub(a: i16, b: i16) -> i16 { (a as u16).saturating_sub(b as u16) as i16 }
+
+fn max_u(a: i16, b: i16) -> i16 { max(a as u16, b as u16) as i16 }
+
+fn min_u(a: i16, b: i16) -> i16 { min(a as u16, b as u16) as i16 }
+
+macro_attr! {
+    #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
+    #[derive(EnumDisplay!, EnumFromStr!)]
+    pub enum Orientation { Horizontal, Vertical }
+}
+
+/// Applies margin, alignment and min/max size to the wrapped layout.
+#[derive(Debug, Clone)]
+pub struct Element<T> {
+    pub child: T,
+    pub margin: Thickness,
+    pub h_align: HAlign,
+    pub v_align: VAlign,
+    pub min_size: Vector,
+    pub max_size: Vector,
+    desired_size: Vector,
+    size: Vector,
+}
+
+impl<T> Element<T> {
+    pub fn new(child: T) -> Self {
+        Element {
+            child,
+            margin: Thickness::default(),
+            h_align: HAlign::Left,
+            v_align: VAlign::Top,
+            min_size: Vector::null(),
+            max_size: Vector { x: UNBOUNDED, y: UNBOUNDED },
+            desired_size: Vector::null(),
+            size: Vector::null(),
+        }
+    }
+
+    /// The size returned by the last `measure` call, including margin.
+    pub fn desired_size(&self) -> Vector { self.desired_size }
+
+    fn clamp(&self, size: Vector) -> Vector {
+        size.min(self.max_size).max(self.min_size)
+    }
+}
+
+impl<T: Layout> Layout for Element<T> {
+    fn measure(&mut self, available: Vector) -> Vector {
+        let child_available = self.margin.shrink_rect_size(available).min(self.max_size);
+        let child_desired = self.child.measure(child_available);
+        self.size = self.clamp(child_desired);
+        self.desired_size = self.margin.expand_rect_size(self.size).min(available);
+        self.desired_size
+    }
+
+    fn arrange(&mut self, final_rect: Rect) -> Rect {
+        let inner = self.margin.shrink_rect(final_rect);
+        let mut size = self.size.min(inner.size);
+        if self.h_align == HAlign::Stretch { size.x = inner.w(); }
+        if self.v_align == VAlign::Stretch { size.y = inner.h(); }
+        let size = self.clamp(size);
+        let h_align = if self.h_align == HAlign::Stretch && size.x != inner.w() { HAlign::Center } else { self.h_align };
+        let v_align = if self.v_align == VAlign::Stretch && size.y != inner.h() { VAlign::Center } else { self.v_align };
+        self.child.arrange(Rect::align_in(inner, size, h_align, v_align))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StackPanel<C> {
+    pub orientation: Orientation,
+    pub children: C,
+}
+
+impl<C: DerefMut<Target=[Element<T>]>, T: Layout> Layout for StackPanel<C> {
+    fn measure(&mut self, available: Vector) -> Vector {
+        let o = self.orientation;
+        let mut along = 0i16;
+        let mut across = 0i16;
+        for child in self.children.deref_mut() {
+            let d = child.measure(o.vector(UNBOUNDED, o.across(available)));
+            along = add(along, o.along(d));
+            across = max_u(across, o.across(d));
+        }
+        o.vector(along, across)
+    }
+
+    fn arrange(&mut self, final_rect: Rect) -> Rect {
+        let o = self.orientation;
+        let mut along = 0i16;
+        for child in self.children.deref_mut() {
+            let d = child.desired_size();
+            let tl = final_rect.tl.offset(o.vector(along, 0));
+            child.arrange(Rect { tl, size: o.vector(o.along(d), o.across(final_rect.size)) });
+            along = add(along, o.along(d));
+        }
+        final_rect
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Docked<T> {
+    pub dock: Side,
+    pub element: Element<T>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DockPanel<C> {
+    pub last_child_fill: bool,
+    pub children: C,
+}
+
+impl<C: DerefMut<Target=[Docked<T>]>, T: Layout> Layout for DockPanel<C> {
+    fn measure(&mut self, available: Vector) -> Vector {
+        let mut size = Vector::null();
+        let mut used = Vector::null();
+        for child in self.children.deref_mut() {
+            let child_available = Vector { x: sub(available.x, used.x), y: sub(available.y, used.y) };
+            let d = child.element.measure(child_available);
+            match child.dock {
+                Side::Left | Side::Right => {
+                    size.y = max_u(size.y, add(used.y, d.y));
+                    used.x = add(used.x, d.x);
+                },
+                Side::Top | Side::Bottom => {
+                    size.x = max_u(size.x, add(used.x, d.x));
+                    used.y = add(used.y, d.y);
+                },
+            }
+        }
+        size.max(used)
+    }
+
+    fn arrange(&mut self, final_rect: Rect) -> Rect {
+        let mut rest = final_rect;
+        let children = self.children.deref_mut();
+        let fill = if self.last_child_fill { children.len().checked_sub(1) } else { None };
+        for (i, child) in children.iter_mut().enumerate() {
+            if Some(i) == fill {
+                child.element.arrange(rest);
+                break;
+            }
+            let d = child.element.desired_size();
+            let w = min_u(d.x, rest.w());
+            let h = min_u(d.y, rest.h());
+            let (rect, margin) = match child.dock {
+                Side::Left => (
+                    Rect { tl: rest.tl, size: Vector { x: w, y: rest.h() } },
+                    Thickness::new(w as u16 as i32, 0, 0, 0)
+                ),
+                Side::Top => (
+                    Rect { tl: rest.tl, size: Vector { x: rest.w(), y: h } },
+                    Thickness::new(0, h as u16 as i32, 0, 0)
+                ),
+                Side::Right => (
+                    Rect { tl: Point { x: rest.r().wrapping_sub(w), y: rest.t() }, size: Vector { x: w, y: rest.h() } },
+                    Thickness::new(0, 0, w as u16 as i32, 0)
+                ),
+                Side::Bottom => (
+                    Rect { tl: Point { x: rest.l(), y: rest.b().wrapping_sub(h) }, size: Vector { x: rest.w(), y: h } },
+                    Thickness::new(0, 0, 0, h as u16 as i32)
+                ),
+            };
+            child.element.arrange(rect);
+            rest = margin.shrink_rect(rest);
+        }
+        final_rect
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Placed<T> {
+    /// Position relative to the canvas top left corner.
+    pub tl: Point,
+    pub element: Element<T>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CanvasPanel<C> {
+    pub children: C,
+}
+
+impl<C: DerefMut<Target=[Placed<T>]>, T: Layout> Layout for CanvasPanel<C> {
+    fn measure(&mut self, _available: Vector) -> Vector {
+        for child in self.children.deref_mut() {
+            child.element.measure(Vector { x: UNBOUNDED, y: UNBOUNDED });
+        }
+        Vector::null()
+    }
+
+    fn arrange(&mut self, final_rect: Rect) -> Rect {
+        for child in self.children.deref_mut() {
+            let tl = child.tl.absolute_with(final_rect.tl);
+            child.element.arrange(Rect { tl, size: child.element.desired_size() });
+        }
+        final_rect
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WrapPanel<C> {
+    pub orientation: Orientation,
+    pub children: C,
+}
+
+impl Orientation {
+    fn along(self, v: Vector) -> i16 {
+        match self {
+            Orientation::Horizontal => v.x,
+            Orientation::Vertical => v.y,
+        }
+    }
+
+    fn across(self, v: Vector) -> i16 {
+        match self {
+            Orientation::Horizontal => v.y,
+            Orientation::Vertical => v.x,
+        }
+    }
+
+    fn vector(self, along: i16, across: i16) -> Vector {
+        match self {
+            Orientation::Horizontal => Vector { x: along, y: across },
+            Orientation::Vertical => Vector { x: across, y: along },
+        }
+    }
+}
+
+fn wrap_line<T>(o: Orientation, children: &[Element<T>], start: usize, limit: i16) -> (usize, i16, i16) {
+    let mut along = 0i16;
+    let mut across = 0i16;
+    let mut end = start;
+    while end < children.len() {
+        let d = children[end].desired_size();
+        let next = add(along, o.along(d));
+        if end != start && next as u16 > limit as u16 { break; }
+        along = next;
+        across = max_u(across, o.across(d));
+        end += 1;
+    }
+    (end, along, across)
+}
+
+impl<C: DerefMut<Target=[Element<T>]>, T: Layout> Layout for WrapPanel<C> {
+    fn measure(&mut self, available: Vector) -> Vector {
+        let o = self.orientation;
+        let children = self.children.deref_mut();
+        for child in children.iter_mut() {
+            child.measure(available);
+        }
+        let mut along = 0i16;
+        let mut across = 0i16;
+        let mut start = 0;
+        while start < children.len() {
+            let (end, line_along, line_across) = wrap_line(o, children, start, o.along(available));
+            along = max_u(along, line_along);
+            across = add(across, line_across);
+            start = end;
+        }
+        o.vector(along, across)
+    }
+
+    fn arrange(&mut self, final_rect: Rect) -> Rect {
+        let o = self.orientation;
+        let children = self.children.deref_mut();
+        let mut across = 0i16;
+        let mut start = 0;
+        while start < children.len() {
+            let (end, _, line_across) = wrap_line(o, children, start, o.along(final_rect.size));
+            let mut along = 0i16;
+            for child in &mut children[start .. end] {
+                let d = child.desired_size();
+                let tl = final_rect.tl.offset(o.vector(along, across));
+                child.arrange(Rect { tl, size: o.vector(o.along(d), line_across) });
+                along = add(along, o.along(d));
+            }
+            across = add(across, line_across);
+            start = end;
+        }
+        final_rect
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum GridLength {
+    Fixed(i16),
+    Auto,
+    /// Proportional share of the space left by fixed and auto definitions.
+    Star(u16),
+}
+
+/// Row or column definition. `min` and `max` are interpreted as `u16`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct GridDefinition {
+    pub length: GridLength,
+    pub min: i16,
+    pub max: i16,
+    offset: i16,
+    size: i16,
+    resolved: bool,
+}
+
+impl GridDefinition {
+    pub fn new(length: GridLength) -> Self {
+        GridDefinition { length, min: 0, max: UNBOUNDED, offset: 0, size: 0, resolved: false }
+    }
+
+    /// Offset from the grid start computed by the last layout pass.
+    pub fn offset(&self) -> i16 { self.offset }
+
+    /// Size computed by the last layout pass.
+    pub fn size(&self) -> i16 { self.size }
+
+    fn clamp(&self, size: i16) -> i16 { max_u(min_u(size, self.max), self.min) }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct GridCell {
+    pub row: usize,
+    pub column: usize,
+    /// Zero span is treated as one.
+    pub row_span: usize,
+    /// Zero span is treated as one.
+    pub column_span: usize,
+}
+
+impl GridCell {
+    pub fn new(row: usize, column: usize) -> Self {
+        GridCell { row, column, row_span: 1, column_span: 1 }
+    }
+
+    pub fn rect(self, container_tl: Point, columns: &[GridDefinition], rows: &[GridDefinition]) -> Rect {
+        let (l, w) = span_range(columns, self.column, self.column_span);
+        let (t, h) = span_range(rows, self.row, self.row_span);
+        Rect { tl: container_tl.offset(Vector { x: l, y: t }), size: Vector { x: w, y: h } }
+    }
+}
+
+fn span_indices(defs_len: usize, start: usize, span: usize) -> (usize, usize) {
+    let start = min(start, defs_len);
+    (start, start.saturating_add(max(span, 1)).min(defs_len))
+}
+
+fn span_range(defs: &[GridDefinition], start: usize, span: usize) -> (i16, i16) {
+    let (start, end) = span_indices(defs.len(), start, span);
+    let offset = if start < defs.len() {
+        defs[start].offset
+    } else {
+        defs.last().map_or(0, |d| add(d.offset, d.size))
+    };
+    (offset, defs[start .. end].iter().fold(0, |s, d| add(s, d.size)))
+}
+
+fn resolve_stars(defs: &mut [GridDefinition], mut rest: i16) {
+    for d in defs.iter_mut() {
+        d.resolved = !matches!(d.length, GridLength::Star(_));
+    }
+    'resolve: loop {
+        let weight = defs.iter().filter(|d| !d.resolved).fold(0u32, |s, d| match d.length {
+            GridLength::Star(w) => s + w as u32,
+            _ => s
+        });
+        let share = |d: &GridDefinition| match d.length {
+            GridLength::Star(w) if weight != 0 => ((rest as u16 as u32) * (w as u32) / weight) as u16 as i16,
+            _ => 0
+        };
+        for d in defs.iter_mut().filter(|d| !d.resolved) {
+            let s = share(d);
+            if s as u16 != d.clamp(s) as u16 {
+                d.size = d.clamp(s);
+                d.resolved = true;
+                rest = sub(rest, d.size);
+                continue 'resolve;
+            }
+        }
+        let mut left = rest;
+        for d in defs.iter_mut().filter(|d| !d.resolved) {
+            d.size = share(d);
+            left = sub(left, d.size);
+        }
+        for d in defs.iter_mut().filter(|d| !d.resolved) {
+            if left == 0 { break; }
+            if d.size as u16 != d.max as u16 {
+                d.size = add(d.size, 1);
+                left = sub(left, 1);
+            }
+        }
+        break;
+    }
+}
+
+/// Computes definition sizes and offsets along one axis.
+///
+/// Items are `(start, span, desired)` triples. Star definitions are treated as auto ones
+/// if `available` is [`UNBOUNDED`].
+pub fn resolve_grid_definitions(
+    defs: &mut [GridDefinition],
+    available: i16,
+    items: impl Iterator<Item=(usize, usize, i16)> + Clone
+) {
+    let stars = available != UNBOUNDED;
+    let is_auto = |d: &GridDefinition| match d.length {
+        GridLength::Fixed(_) => false,
+        GridLength::Auto => true,
+        GridLength::Star(_) => !stars,
+    };
+    let is_star = |d: &GridDefinition| stars && matches!(d.length, GridLength::Star(_));
+    for d in defs.iter_mut() {
+        d.size = d.clamp(match d.length { GridLength::Fixed(n) => n, _ => 0 });
+    }
+    for (start, span, desired) in items.clone() {
+        let (start, end) = span_indices(defs.len(), start, span);
+        if end - start != 1 || !is_auto(&defs[start]) { continue; }
+        defs[start].size = defs[start].clamp(max_u(defs[start].size, desired));
+    }
+    for (start, span, desired) in items {
+        let (start, end) = span_indices(defs.len(), start, span);
+        let defs = &mut defs[start .. end];
+        if defs.len() < 2 || defs.iter().any(is_star) { continue; }
+        let mut deficit = sub(desired, defs.iter().fold(0, |s, d| add(s, d.size)));
+        let autos = defs.iter().filter(|d| is_auto(d)).count();
+        if deficit == 0 || autos == 0 { continue; }
+        let part = ((deficit as u16 as usize) / autos) as u16 as i16;
+        let mut extra = ((deficit as u16 as usize) % autos) as u16 as i16;
+        for d in defs.iter_mut().filter(|d| is_auto(d)) {
+            let inc = if extra != 0 { extra -= 1; add(part, 1) } else { part };
+            let size = d.clamp(add(d.size, inc));
+            deficit = sub(deficit, sub(size, d.size));
+            d.size = size;
+        }
+    }
+    if defs.iter().any(is_star) {
+        let used = defs.iter().filter(|d| !is_star(d)).fold(0, |s, d| add(s, d.size));
+        resolve_stars(defs, sub(available, used));
+    }
+    let mut offset = 0i16;
+    for d in defs.iter_mut() {
+        d.offset = offset;
+        offset = add(offset, d.size);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct GridItem {
+    pub cell: GridCell,
+    pub size: Vector,
+    pub h_align: HAlign,
+    pub v_align: VAlign,
+}
+
+/// Resolves definitions for the `container` and writes every item rect into `rects`.
+pub fn arrange_grid(
+    container: Rect,
+    columns: &mut [GridDefinition],
+    rows: &mut [GridDefinition],
+    items: &[GridItem],
+    rects: &mut [Rect]
+) {
+    resolve_grid_definitions(columns, container.w(), items.iter().map(|x| (x.cell.column, x.cell.column_span, x.size.x)));
+    resolve_grid_definitions(rows, container.h(), items.iter().map(|x| (x.cell.row, x.cell.row_span, x.size.y)));
+    for (item, rect) in items.iter().zip(rects.iter_mut()) {
+        let cell = item.cell.rect(container.tl, columns, rows);
+        *rect = Rect::align_in(cell, item.size.min(cell.size), item.h_align, item.v_align);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Gridded<T> {
+    pub cell: GridCell,
+    pub element: Element<T>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GridPanel<D, C> {
+    pub columns: D,
+    pub rows: D,
+    pub children: C,
+}
+
+impl<D: DerefMut<Target=[GridDefinition]>, C: DerefMut<Target=[Gridded<T>]>, T: Layout> Layout for GridPanel<D, C> {
+    fn measure(&mut self, available: Vector) -> Vector {
+        for child in self.children.deref_mut() {
+            child.element.measure(available);
+        }
+        let children = &self.children[..];
+        let columns = children.iter().map(|x| (x.cell.column, x.cell.column_span, x.element.desired_size().x));
+        let rows = children.iter().map(|x| (x.cell.row, x.cell.row_span, x.element.desired_size().y));
+        resolve_grid_definitions(&mut self.columns, UNBOUNDED, columns);
+        resolve_grid_definitions(&mut self.rows, UNBOUNDED, rows);
+        Vector {
+            x: self.columns.iter().fold(0, |s, d| add(s, d.size)),
+            y: self.rows.iter().fold(0, |s, d| add(s, d.size)),
+        }
+    }
+
+    fn arrange(&mut self, final_rect: Rect) -> Rect {
+        let children = &self.children[..];
+        let columns = children.iter().map(|x| (x.cell.column, x.cell.column_span, x.element.desired_size().x));
+        let rows = children.iter().map(|x| (x.cell.row, x.cell.row_span, x.element.desired_size().y));
+        resolve_grid_definitions(&mut self.columns, final_rect.w(), columns);
+        resolve_grid_definitions(&mut self.rows, final_rect.h(), rows);
+        for child in self.children.deref_mut() {
+            let cell = child.cell.rect(final_rect.tl, &self.columns, &self.rows);
+            child.element.arrange(cell);
+        }
+        final_rect
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use crate::layout::*;
+
+    struct Fixed(Vector, Rect);
+
+    impl Layout for Fixed {
+        fn measure(&mut self, _available: Vector) -> Vector { self.0 }
+
+        fn arrange(&mut self, final_rect: Rect) -> Rect {
+            self.1 = final_rect;
+            final_rect
+        }
+    }
+
+    fn fixed(x: i16, y: i16) -> Element<Fixed> {
+        Element::new(Fixed(Vector { x, y }, Rect { tl: Point { x: 0, y: 0 }, size: Vector::null() }))
+    }
+
+    fn rect(l: i16, t: i16, w: i16, h: i16) -> Rect {
+        Rect { tl: Point { x: l, y: t }, size: Vector { x: w, y: h } }
+    }
+
+    #[test]
+    fn element_margin_and_alignment() {
+        let mut e = fixed(4, 2);
+        e.margin = Thickness::all(1);
+        e.h_align = HAlign::Right;
+        e.v_align = VAlign::Center;
+        assert_eq!(e.measure(Vector { x: 20, y: 20 }), Vector { x: 6, y: 4 });
+        assert_eq!(e.arrange(rect(0, 0, 10, 10)), rect(5, 4, 4, 2));
+    }
+
+    #[test]
+    fn element_stretch() {
+        let mut e = fixed(4, 2);
+        e.h_align = HAlign::Stretch;
+        e.v_align = VAlign::Stretch;
+        e.max_size = Vector { x: 6, y: UNBOUNDED };
+        e.measure(Vector { x: 20, y: 20 });
+        assert_eq!(e.arrange(rect(0, 0, 10, 10)), rect(2, 0, 6, 10));
+    }
+
+    #[test]
+    fn element_min_max_size() {
+        let mut e = fixed(4, 2);
+        e.min_size = Vector { x: 0, y: 3 };
+        e.max_size = Vector { x: 3, y: UNBOUNDED };
+        assert_eq!(e.measure(Vector { x: 20, y: 20 }), Vector { x: 3, y: 3 });
+    }
+
+    #[test]
+    fn stack_panel() {
+        let mut p = StackPanel { orientation: Orientation::Vertical, children: &mut [fixed(3, 1), fixed(5, 2)][..] };
+        assert_eq!(p.measure(Vector { x: 10, y: 10 }), Vector { x: 5, y: 3 });
+        p.arrange(rect(1, 1, 10, 10));
+        assert_eq!(p.children[0].child.1, rect(1, 1, 3, 1));
+        assert_eq!(p.children[1].child.1, rect(1, 2, 5, 2));
+    }
+
+    #[test]
+    fn dock_panel() {
+        let mut p = DockPanel {
+            last_child_fill: true,
+            children: &mut [
+                Docked { dock: Side::Top, element: fixed(1, 1) },
+                Docked { dock: Side::Left, element: fixed(2, 1) },
+                Docked { dock: Side::Left, element: fixed(1, 1) },
+            ][..]
+        };
+        assert_eq!(p.measure(Vector { x: 10, y: 10 }), Vector { x: 3, y: 2 });
+        p.arrange(rect(0, 0, 10, 5));
+        assert_eq!(p.children[0].element.child.1, rect(0, 0, 1, 1));
+        assert_eq!(p.children[1].element.child.1, rect(0, 1, 2, 1));
+        assert_eq!(p.children[2].element.child.1, rect(2, 1, 1, 1));
+    }
+
+    #[test]
+    fn canvas_panel() {
+        let mut p = CanvasPanel { children: &mut [Placed { tl: Point { x: 2, y: 3 }, element: fixed(4, 4) }][..] };
+        assert_eq!(p.measure(Vector { x: 10, y: 10 }), Vector::null());
+        p.arrange(rect(10, 10, 10, 10));
+        assert_eq!(p.children[0].element.child.1, rect(12, 13, 4, 4));
+    }
+
+    #[test]
+    fn wrap_panel() {
+        let mut p = WrapPanel {
+            orientation: Orientation::Horizontal,
+            children: &mut [fixed(3, 1), fixed(3, 2), fixed(3, 1)][..]
+        };
+        assert_eq!(p.measure(Vector { x: 7, y: 10 }), Vector { x: 6, y: 3 });
+        p.arrange(rect(0, 0, 7, 10));
+        assert_eq!(p.children[0].child.1, rect(0, 0, 3, 1));
+        assert_eq!(p.children[1].child.1, rect(3, 0, 3, 2));
+        assert_eq!(p.children[2].child.1, rect(0, 2, 3, 1));
+    }
+
+    fn def(length: GridLength) -> GridDefinition { GridDefinition::new(length) }
+
+    #[test]
+    fn grid_fixed_auto_star() {
+        let mut columns = [def(GridLength::Fixed(3)), def(GridLength::Auto), def(GridLength::Star(1)), def(GridLength::Star(2))];
+        let mut rows = [def(GridLength::Auto), def(GridLength::Star(1))];
+        let items = [
+            GridItem { cell: GridCell::new(0, 1), size: Vector { x: 4, y: 2 }, h_align: HAlign::Left, v_align: VAlign::Top },
+            GridItem { cell: GridCell::new(1, 3), size: Vector { x: 2, y: 2 }, h_align: HAlign::Right, v_align: VAlign::Bottom },
+        ];
+        let mut rects = [Rect { tl: Point { x: 0, y: 0 }, size: Vector::null() }; 2];
+        arrange_grid(rect(10, 10, 16, 10), &mut columns, &mut rows, &items, &mut rects);
+        assert_eq!(columns.map(|x| x.size()), [3, 4, 3, 6]);
+        assert_eq!(rows.map(|x| x.size()), [2, 8]);
+        assert_eq!(rects, [rect(13, 10, 4, 2), rect(24, 18, 2, 2)]);
+    }
+
+    #[test]
+    fn grid_span_and_star_limits() {
+        let mut columns = [def(GridLength::Auto), def(GridLength::Auto), def(GridLength::Star(1)), def(GridLength::Star(1))];
+        columns[2].max = 2;
+        let mut rows = [def(GridLength::Star(1))];
+        let mut cell = GridCell::new(0, 0);
+        cell.column_span = 2;
+        let items = [GridItem { cell, size: Vector { x: 5, y: 1 }, h_align: HAlign::Center, v_align: VAlign::Center }];
+        let mut rects = [Rect { tl: Point { x: 0, y: 0 }, size: Vector::null() }];
+        arrange_grid(rect(0, 0, 12, 3), &mut columns, &mut rows, &items, &mut rects);
+        assert_eq!(columns.map(|x| x.size()), [3, 2, 2, 5]);
+        assert_eq!(rects, [rect(0, 1, 5, 1)]);
+    }
+
+    #[test]
+    fn grid_panel() {
+        let mut p = GridPanel {
+            columns: &mut [def(GridLength::Auto), def(GridLength::Star(1))][..],
+            rows: &mut [def(GridLength::Auto), def(GridLength::Auto)][..],
+            children: &mut [
+                Gridded { cell: GridCell::new(0, 0), element: fixed(2, 1) },
+                Gridded { cell: GridCell::new(1, 1), element: fixed(3, 2) },
+            ][..]
+        };
+        assert_eq!(p.measure(Vector { x: 10, y: 10 }), Vector { x: 5, y: 3 });
+        p.arrange(rect(0, 0, 10, 10));
+        assert_eq!(p.children[0].element.child.1, rect(0, 0, 2, 1));
+        assert_eq!(p.children[1].element.child.1, rect(2, 1, 3, 2));
+    }
+}
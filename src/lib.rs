@@ -15,17 +15,44 @@
 
 #![no_std]
 
+#[cfg(feature="alloc")]
+extern crate alloc;
+
 use core::cmp::{min, max};
-use core::iter::{FusedIterator, TrustedLen};
+use core::fmt::{self, Display, Formatter};
+use core::iter::{FusedIterator, Rev, TrustedLen};
 use core::num::{NonZeroI16, NonZeroUsize};
+use core::iter::Sum;
 use core::ops::{Add, AddAssign, Sub, SubAssign, Neg, Index, IndexMut};
+use core::ops::{Mul, MulAssign, Div, DivAssign, Rem, RemAssign};
 use either::{Either, Left, Right};
 use enum_derive_2018::{EnumDisplay, EnumFromStr};
 use macro_attr_2018::macro_attr;
 use num_traits::Zero;
+use traverse::{ColumnMajorPoints, HilbertPoints, RingPoints, SerpentinePoints, SpiralPoints, ZOrderPoints};
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
 
+#[cfg(feature="alloc")]
+pub mod atlas;
+#[cfg(feature="alloc")]
+pub mod components;
+#[cfg(feature="alloc")]
+pub mod contour;
+#[cfg(feature="alloc")]
+pub mod decompose;
+#[cfg(feature="alloc")]
+pub mod distance;
+pub mod grid;
+pub mod layout;
+#[cfg(feature="alloc")]
+pub mod morphology;
+pub mod pack;
+pub mod scale;
+pub mod transform;
+pub mod traverse;
+pub mod units;
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Range1d {
     pub start: i16,
@@ -39,6 +66,59 @@ impl Range1d {
 
     pub fn len(self) -> u16 { self.end.wrapping_sub(self.start) as u16 }
 
+    /// Returns an error instead of a range wrapping around `i16::MAX`.
+    pub fn try_new(start: i16, end: i16) -> Result<Self, GeometryError> {
+        if end < start { Err(GeometryError { bound: Side::Right }) } else { Ok(Range1d { start, end }) }
+    }
+
+    /// Moves `start` back so the range does not wrap around `i16::MAX`. The length is kept.
+    pub fn saturating_new(start: i16, end: i16) -> Self {
+        Range1d::new(start, end).saturating_offset(0)
+    }
+
+    /// Returns `None` if the range wraps around `i16::MAX`.
+    pub fn checked_len(self) -> Option<u16> {
+        if self.end < self.start { None } else { Some(self.len()) }
+    }
+
+    pub fn offset(self, d: i16) -> Range1d {
+        Range1d { start: self.start.wrapping_add(d), end: self.end.wrapping_add(d) }
+    }
+
+    pub fn checked_offset(self, d: i16) -> Option<Range1d> {
+        let start = self.start.checked_add(d)?;
+        Some(Range1d { start, end: checked_end(start, self.checked_len()?, Side::Right).ok()? })
+    }
+
+    /// Extends the range to the multiples of `step` counted from `origin`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    pub fn snap_outward(self, step: u16, origin: i16) -> Range1d {
+        let start = snap_floor(self.start as i32, step, origin);
+        let end = snap_ceil(self.start as i32 + self.len() as i32, step, origin);
+        Range1d::new(start as i16, end as i16)
+    }
+
+    /// Shrinks the range to the multiples of `step` counted from `origin`.
+    /// The result is empty if the range does not contain a whole step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    pub fn snap_inward(self, step: u16, origin: i16) -> Range1d {
+        let start = snap_ceil(self.start as i32, step, origin);
+        let end = snap_floor(self.start as i32 + self.len() as i32, step, origin);
+        Range1d::new(start as i16, max(start, end) as i16)
+    }
+
+    /// Moves the range as far as possible without wrapping. The length is kept.
+    pub fn saturating_offset(self, d: i16) -> Range1d {
+        let start = saturating_start(self.start, d as i32, self.len());
+        Range1d { start, end: start.wrapping_add(self.len() as i16) }
+    }
+
     pub fn inclusive(start: i16, end: i16) -> Option<Self> {
         let res = Range1d { start, end: end.wrapping_add(1) };
         if res.is_empty() { None } else { Some(res) }
@@ -138,7 +218,7 @@ impl Iterator for Range1d {
     fn count(self) -> usize { self.len() as usize }
 
     fn last(self) -> Option<i16> {
-        if self.is_empty() { None } else { Some(self.end) }
+        if self.is_empty() { None } else { Some(self.end.wrapping_sub(1)) }
     }
 
     fn advance_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
@@ -156,9 +236,8 @@ impl FusedIterator for Range1d { }
 impl DoubleEndedIterator for Range1d {
     fn next_back(&mut self) -> Option<i16> {
         if !self.is_empty() {
-            let item = self.end;
             self.end = self.end.wrapping_sub(1);
-            Some(item)
+            Some(self.end)
         } else {
             None
         }
@@ -193,6 +272,87 @@ macro_attr! {
     }
 }
 
+/// Coordinate overflow past the `bound` side of the `i16` plane.
+///
+/// For axis-agnostic values (e.g. [`Range1d`]) `Left` and `Right` stand for the minimum and the maximum.
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
+pub struct GeometryError {
+    pub bound: Side,
+}
+
+impl Display for GeometryError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "coordinate overflow at {} plane bound", self.bound)
+    }
+}
+
+impl core::error::Error for GeometryError { }
+
+fn checked_end(start: i16, len: u16, bound: Side) -> Result<i16, GeometryError> {
+    let end = start as i32 + len as i32;
+    if end > i16::MAX as i32 { Err(GeometryError { bound }) } else { Ok(end as i16) }
+}
+
+fn snap_floor(coord: i32, step: u16, origin: i16) -> i32 {
+    assert!(step != 0, "zero snap step");
+    let origin = origin as i32;
+    origin + (coord - origin).div_euclid(step as i32) * step as i32
+}
+
+fn snap_ceil(coord: i32, step: u16, origin: i16) -> i32 {
+    let floor = snap_floor(coord, step, origin);
+    if floor == coord { floor } else { floor + step as i32 }
+}
+
+fn chunk_index(coord: i16, step: u16, origin: i16) -> i16 {
+    assert!(step != 0, "zero tile size");
+    (coord as i32 - origin as i32).div_euclid(step as i32) as i16
+}
+
+fn saturating_start(start: i16, d: i32, len: u16) -> i16 {
+    let max = max(i16::MAX as i32 - len as i32, i16::MIN as i32);
+    min(max, start as i32 + d).max(i16::MIN as i32) as i16
+}
+
+/// The `size_hint` of an iterator with `len` items left, which may not fit `usize`.
+fn exact_size_hint(len: u32) -> (usize, Option<usize>) {
+    if len as usize as u32 == len {
+        (len as usize, Some(len as usize))
+    } else {
+        (usize::MAX, None)
+    }
+}
+
+fn bias(coord: i16) -> u16 { coord as u16 ^ 0x8000 }
+
+fn unbias(coord: u16) -> i16 { (coord ^ 0x8000) as i16 }
+
+fn spread_bits(v: u16) -> u32 {
+    let mut x = v as u32;
+    x = (x | (x << 8)) & 0x00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F;
+    x = (x | (x << 2)) & 0x3333_3333;
+    (x | (x << 1)) & 0x5555_5555
+}
+
+fn compact_bits(x: u32) -> u16 {
+    let mut x = x & 0x5555_5555;
+    x = (x | (x >> 1)) & 0x3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF;
+    (x | (x >> 8)) as u16
+}
+
+fn hilbert_rotate(s: u32, x: &mut u32, y: &mut u32, rx: u32, ry: u32) {
+    if ry == 0 {
+        if rx == 1 {
+            *x = s - 1 - *x;
+            *y = s - 1 - *y;
+        }
+        core::mem::swap(x, y);
+    }
+}
+
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
 pub struct Point {
     pub x: i16,
@@ -215,6 +375,137 @@ impl Point {
     pub fn absolute_with(self, base: Point) -> Point {
         Point { x: self.x.wrapping_add(base.x), y: self.y.wrapping_add(base.y) }
     }
+
+    pub fn checked_offset(self, d: Vector) -> Option<Point> {
+        Some(Point { x: self.x.checked_add(d.x)?, y: self.y.checked_add(d.y)? })
+    }
+
+    /// Interleaves the coordinate bits, `x` going to the even ones.
+    ///
+    /// Coordinates are biased by `2¹⁵` (`i16::MIN` maps to zero), so the code order
+    /// agrees with the coordinate order along each axis.
+    pub fn to_morton(self) -> u32 {
+        spread_bits(bias(self.x)) | (spread_bits(bias(self.y)) << 1)
+    }
+
+    pub fn from_morton(code: u32) -> Point {
+        Point { x: unbias(compact_bits(code)), y: unbias(compact_bits(code >> 1)) }
+    }
+
+    /// The point index along the Hilbert curve of the given `order`
+    /// filling a `2ᵒʳᵈᵉʳ × 2ᵒʳᵈᵉʳ` square.
+    ///
+    /// The plane is tiled with such squares aligned to multiples of `2ᵒʳᵈᵉʳ`,
+    /// the index is the point position in the square containing it.
+    /// The curve starts at the top left square corner and ends at the top right one.
+    /// For `order` 16 the square is the whole plane, with coordinates biased as in [`Point::to_morton`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than 16.
+    pub fn to_hilbert(self, order: u8) -> u32 {
+        assert!(order <= 16, "Hilbert curve order too big");
+        let mask = ((1u32 << order) - 1) as u16 as u32;
+        let (mut x, mut y) = (bias(self.x) as u32 & mask, bias(self.y) as u32 & mask);
+        let mut d = 0;
+        let mut s = 1u32 << order >> 1;
+        while s > 0 {
+            let rx = (x & s != 0) as u32;
+            let ry = (y & s != 0) as u32;
+            d += s * s * ((3 * rx) ^ ry);
+            x &= s - 1;
+            y &= s - 1;
+            hilbert_rotate(s, &mut x, &mut y, rx, ry);
+            s >>= 1;
+        }
+        d
+    }
+
+    /// The point at the `index` along the Hilbert curve of the given `order`
+    /// in the square containing the origin, see [`Point::to_hilbert`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is greater than 16, or if `index` is out of the curve.
+    pub fn from_hilbert(index: u32, order: u8) -> Point {
+        assert!(order <= 16, "Hilbert curve order too big");
+        assert!(order == 16 || index < 1 << (2 * order), "Hilbert curve index out of bounds");
+        let (mut x, mut y) = (0, 0);
+        let mut t = index;
+        let mut s = 1u32;
+        while s < 1 << order {
+            let rx = 1 & (t / 2);
+            let ry = 1 & (t ^ rx);
+            hilbert_rotate(s, &mut x, &mut y, rx, ry);
+            x += s * rx;
+            y += s * ry;
+            t /= 4;
+            s <<= 1;
+        }
+        if order == 16 {
+            Point { x: unbias(x as u16), y: unbias(y as u16) }
+        } else {
+            Point { x: x as u16 as i16, y: y as u16 as i16 }
+        }
+    }
+
+    /// Rounds down to the grid of `step` sized cells with a node at `origin`.
+    ///
+    /// `step` components are interpreted as `u16`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any `step` component is zero.
+    pub fn snap_to(self, step: Vector, origin: Point) -> Point {
+        Point {
+            x: snap_floor(self.x as i32, step.x as u16, origin.x) as i16,
+            y: snap_floor(self.y as i32, step.y as u16, origin.y) as i16,
+        }
+    }
+
+    /// The coordinates of the `tile` sized chunk containing the point,
+    /// chunk `(0, 0)` having its top left corner at `origin`.
+    ///
+    /// `tile` components are interpreted as `u16`. Chunk coordinates wrap
+    /// if they do not fit (only possible for the unit tile size).
+    ///
+    /// # Panics
+    ///
+    /// Panics if any `tile` component is zero.
+    pub fn chunk(self, tile: Vector, origin: Point) -> Point {
+        Point {
+            x: chunk_index(self.x, tile.x as u16, origin.x),
+            y: chunk_index(self.y, tile.y as u16, origin.y),
+        }
+    }
+
+    pub fn saturating_offset(self, d: Vector) -> Point {
+        Point { x: self.x.saturating_add(d.x), y: self.y.saturating_add(d.y) }
+    }
+
+    pub fn checked_offset_from(self, other: Point) -> Option<Vector> {
+        Some(Vector { x: self.x.checked_sub(other.x)?, y: self.y.checked_sub(other.y)? })
+    }
+
+    pub fn saturating_offset_from(self, other: Point) -> Vector {
+        Vector { x: self.x.saturating_sub(other.x), y: self.y.saturating_sub(other.y) }
+    }
+
+    pub fn checked_relative_to(self, base: Point) -> Option<Point> {
+        Some(Point { x: self.x.checked_sub(base.x)?, y: self.y.checked_sub(base.y)? })
+    }
+
+    pub fn saturating_relative_to(self, base: Point) -> Point {
+        Point { x: self.x.saturating_sub(base.x), y: self.y.saturating_sub(base.y) }
+    }
+
+    pub fn checked_absolute_with(self, base: Point) -> Option<Point> {
+        Some(Point { x: self.x.checked_add(base.x)?, y: self.y.checked_add(base.y)? })
+    }
+
+    pub fn saturating_absolute_with(self, base: Point) -> Point {
+        Point { x: self.x.saturating_add(base.x), y: self.y.saturating_add(base.y) }
+    }
 }
 
 #[cfg(test)]
@@ -251,6 +542,155 @@ impl Vector {
             y: min(self.y as u16, other.y as u16) as i16,
         }
     }
+
+    pub fn checked_add(self, other: Vector) -> Option<Vector> {
+        Some(Vector { x: self.x.checked_add(other.x)?, y: self.y.checked_add(other.y)? })
+    }
+
+    pub fn saturating_add(self, other: Vector) -> Vector {
+        Vector { x: self.x.saturating_add(other.x), y: self.y.saturating_add(other.y) }
+    }
+
+    pub fn checked_sub(self, other: Vector) -> Option<Vector> {
+        Some(Vector { x: self.x.checked_sub(other.x)?, y: self.y.checked_sub(other.y)? })
+    }
+
+    pub fn saturating_sub(self, other: Vector) -> Vector {
+        Vector { x: self.x.saturating_sub(other.x), y: self.y.saturating_sub(other.y) }
+    }
+
+    pub fn checked_neg(self) -> Option<Vector> {
+        Some(Vector { x: self.x.checked_neg()?, y: self.y.checked_neg()? })
+    }
+
+    pub fn saturating_neg(self) -> Vector {
+        Vector { x: self.x.saturating_neg(), y: self.y.saturating_neg() }
+    }
+
+    pub fn checked_mul(self, k: i16) -> Option<Vector> {
+        Some(Vector { x: self.x.checked_mul(k)?, y: self.y.checked_mul(k)? })
+    }
+
+    pub fn saturating_mul(self, k: i16) -> Vector {
+        Vector { x: self.x.saturating_mul(k), y: self.y.saturating_mul(k) }
+    }
+
+    /// Returns `None` if `k` is zero or the division overflows.
+    pub fn checked_div(self, k: i16) -> Option<Vector> {
+        Some(Vector { x: self.x.checked_div(k)?, y: self.y.checked_div(k)? })
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    pub fn saturating_div(self, k: i16) -> Vector {
+        Vector { x: self.x.saturating_div(k), y: self.y.saturating_div(k) }
+    }
+
+    pub fn checked_rem(self, k: i16) -> Option<Vector> {
+        Some(Vector { x: self.x.checked_rem(k)?, y: self.y.checked_rem(k)? })
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    pub fn div_euclid(self, k: i16) -> Vector {
+        Vector { x: self.x.wrapping_div_euclid(k), y: self.y.wrapping_div_euclid(k) }
+    }
+
+    pub fn checked_div_euclid(self, k: i16) -> Option<Vector> {
+        Some(Vector { x: self.x.checked_div_euclid(k)?, y: self.y.checked_div_euclid(k)? })
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    pub fn rem_euclid(self, k: i16) -> Vector {
+        Vector { x: self.x.wrapping_rem_euclid(k), y: self.y.wrapping_rem_euclid(k) }
+    }
+
+    pub fn checked_rem_euclid(self, k: i16) -> Option<Vector> {
+        Some(Vector { x: self.x.checked_rem_euclid(k)?, y: self.y.checked_rem_euclid(k)? })
+    }
+
+    pub fn component_mul(self, other: Vector) -> Vector {
+        Vector { x: self.x.wrapping_mul(other.x), y: self.y.wrapping_mul(other.y) }
+    }
+
+    pub fn checked_component_mul(self, other: Vector) -> Option<Vector> {
+        Some(Vector { x: self.x.checked_mul(other.x)?, y: self.y.checked_mul(other.y)? })
+    }
+
+    pub fn saturating_component_mul(self, other: Vector) -> Vector {
+        Vector { x: self.x.saturating_mul(other.x), y: self.y.saturating_mul(other.y) }
+    }
+
+    fn dot_parts(self, other: Vector) -> (i32, i32) {
+        (self.x as i32 * other.x as i32, self.y as i32 * other.y as i32)
+    }
+
+    pub fn dot(self, other: Vector) -> i32 {
+        let (a, b) = self.dot_parts(other);
+        a.wrapping_add(b)
+    }
+
+    /// Returns `None` only if both components are `i16::MIN` in both vectors.
+    pub fn checked_dot(self, other: Vector) -> Option<i32> {
+        let (a, b) = self.dot_parts(other);
+        a.checked_add(b)
+    }
+
+    pub fn saturating_dot(self, other: Vector) -> i32 {
+        let (a, b) = self.dot_parts(other);
+        a.saturating_add(b)
+    }
+
+    /// Never overflows.
+    pub fn cross(self, other: Vector) -> i32 {
+        self.x as i32 * other.y as i32 - self.y as i32 * other.x as i32
+    }
+
+    pub fn abs(self) -> Vector {
+        Vector { x: self.x.wrapping_abs(), y: self.y.wrapping_abs() }
+    }
+
+    pub fn checked_abs(self) -> Option<Vector> {
+        Some(Vector { x: self.x.checked_abs()?, y: self.y.checked_abs()? })
+    }
+
+    pub fn saturating_abs(self) -> Vector {
+        Vector { x: self.x.saturating_abs(), y: self.y.saturating_abs() }
+    }
+
+    pub fn signum(self) -> Vector {
+        Vector { x: self.x.signum(), y: self.y.signum() }
+    }
+
+    /// Rotates by 90° clockwise on screen (the `y` axis points down).
+    pub fn perp_cw(self) -> Vector {
+        Vector { x: self.y.wrapping_neg(), y: self.x }
+    }
+
+    pub fn checked_perp_cw(self) -> Option<Vector> {
+        Some(Vector { x: self.y.checked_neg()?, y: self.x })
+    }
+
+    pub fn saturating_perp_cw(self) -> Vector {
+        Vector { x: self.y.saturating_neg(), y: self.x }
+    }
+
+    /// Rotates by 90° counterclockwise on screen (the `y` axis points down).
+    pub fn perp_ccw(self) -> Vector {
+        Vector { x: self.y, y: self.x.wrapping_neg() }
+    }
+
+    pub fn checked_perp_ccw(self) -> Option<Vector> {
+        Some(Vector { x: self.y, y: self.x.checked_neg()? })
+    }
+
+    pub fn saturating_perp_ccw(self) -> Vector {
+        Vector { x: self.y, y: self.x.saturating_neg() }
+    }
 }
 
 impl Default for Vector {
@@ -301,6 +741,60 @@ impl Neg for Vector {
     }
 }
 
+impl Mul<i16> for Vector {
+    type Output = Self;
+
+    fn mul(self, k: i16) -> Self {
+        Vector { x: self.x.wrapping_mul(k), y: self.y.wrapping_mul(k) }
+    }
+}
+
+impl MulAssign<i16> for Vector {
+    fn mul_assign(&mut self, k: i16) {
+        *self = *self * k;
+    }
+}
+
+impl Div<i16> for Vector {
+    type Output = Self;
+
+    fn div(self, k: i16) -> Self {
+        Vector { x: self.x.wrapping_div(k), y: self.y.wrapping_div(k) }
+    }
+}
+
+impl DivAssign<i16> for Vector {
+    fn div_assign(&mut self, k: i16) {
+        *self = *self / k;
+    }
+}
+
+impl Rem<i16> for Vector {
+    type Output = Self;
+
+    fn rem(self, k: i16) -> Self {
+        Vector { x: self.x.wrapping_rem(k), y: self.y.wrapping_rem(k) }
+    }
+}
+
+impl RemAssign<i16> for Vector {
+    fn rem_assign(&mut self, k: i16) {
+        *self = *self % k;
+    }
+}
+
+impl Sum for Vector {
+    fn sum<I: Iterator<Item=Vector>>(iter: I) -> Self {
+        iter.fold(Vector::null(), |s, x| s + x)
+    }
+}
+
+impl<'a> Sum<&'a Vector> for Vector {
+    fn sum<I: Iterator<Item=&'a Vector>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
 #[cfg(test)]
 impl Arbitrary for Vector {
     fn arbitrary(g: &mut Gen) -> Self {
@@ -324,8 +818,28 @@ impl VBand {
         VBand::from_l_r(h_range.start, h_range.end)
     }
 
+    pub fn try_new(l: i16, w: NonZeroI16) -> Result<VBand, GeometryError> {
+        checked_end(l, w.get() as u16, Side::Right).map(|_| VBand { l, w })
+    }
+
+    /// Moves `l` back so the band does not wrap around `i16::MAX`. The width is kept.
+    pub fn saturating_new(l: i16, w: NonZeroI16) -> VBand {
+        VBand { l: saturating_start(l, 0, w.get() as u16), w }
+    }
+
     pub fn r(self) -> i16 { self.l.wrapping_add(self.w.get()) }
 
+    pub fn checked_r(self) -> Option<i16> { checked_end(self.l, self.w.get() as u16, Side::Right).ok() }
+
+    pub fn checked_offset(self, d: Vector) -> Option<VBand> {
+        let l = self.l.checked_add(d.x)?;
+        VBand::try_new(l, self.w).ok()
+    }
+
+    pub fn saturating_offset(self, d: Vector) -> VBand {
+        VBand { l: saturating_start(self.l, d.x as i32, self.w.get() as u16), w: self.w }
+    }
+
     pub fn h_range(self) -> Range1d { Range1d::new(self.l, self.r()) }
 
     pub fn offset(self, d: Vector) -> VBand {
@@ -339,6 +853,22 @@ impl VBand {
     pub fn absolute_with(self, base: Point) -> VBand {
         VBand { l: self.l.wrapping_add(base.x), w: self.w }
     }
+
+    pub fn checked_relative_to(self, base: Point) -> Option<VBand> {
+        VBand::try_new(self.l.checked_sub(base.x)?, self.w).ok()
+    }
+
+    pub fn saturating_relative_to(self, base: Point) -> VBand {
+        VBand { l: saturating_start(self.l, -(base.x as i32), self.w.get() as u16), w: self.w }
+    }
+
+    pub fn checked_absolute_with(self, base: Point) -> Option<VBand> {
+        VBand::try_new(self.l.checked_add(base.x)?, self.w).ok()
+    }
+
+    pub fn saturating_absolute_with(self, base: Point) -> VBand {
+        VBand { l: saturating_start(self.l, base.x as i32, self.w.get() as u16), w: self.w }
+    }
 }
 
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
@@ -356,8 +886,28 @@ impl HBand {
         HBand::from_t_b(v_range.start, v_range.end)
     }
 
+    pub fn try_new(t: i16, h: NonZeroI16) -> Result<HBand, GeometryError> {
+        checked_end(t, h.get() as u16, Side::Bottom).map(|_| HBand { t, h })
+    }
+
+    /// Moves `t` back so the band does not wrap around `i16::MAX`. The height is kept.
+    pub fn saturating_new(t: i16, h: NonZeroI16) -> HBand {
+        HBand { t: saturating_start(t, 0, h.get() as u16), h }
+    }
+
     pub fn b(self) -> i16 { self.t.wrapping_add(self.h.get()) }
 
+    pub fn checked_b(self) -> Option<i16> { checked_end(self.t, self.h.get() as u16, Side::Bottom).ok() }
+
+    pub fn checked_offset(self, d: Vector) -> Option<HBand> {
+        let t = self.t.checked_add(d.y)?;
+        HBand::try_new(t, self.h).ok()
+    }
+
+    pub fn saturating_offset(self, d: Vector) -> HBand {
+        HBand { t: saturating_start(self.t, d.y as i32, self.h.get() as u16), h: self.h }
+    }
+
     pub fn v_range(self) -> Range1d { Range1d::new(self.t, self.b()) }
 
     pub fn offset(self, d: Vector) -> HBand {
@@ -371,6 +921,22 @@ impl HBand {
     pub fn absolute_with(self, base: Point) -> HBand {
         HBand { t: self.t.wrapping_add(base.y), h: self.h }
     }
+
+    pub fn checked_relative_to(self, base: Point) -> Option<HBand> {
+        HBand::try_new(self.t.checked_sub(base.y)?, self.h).ok()
+    }
+
+    pub fn saturating_relative_to(self, base: Point) -> HBand {
+        HBand { t: saturating_start(self.t, -(base.y as i32), self.h.get() as u16), h: self.h }
+    }
+
+    pub fn checked_absolute_with(self, base: Point) -> Option<HBand> {
+        HBand::try_new(self.t.checked_add(base.y)?, self.h).ok()
+    }
+
+    pub fn saturating_absolute_with(self, base: Point) -> HBand {
+        HBand { t: saturating_start(self.t, base.y as i32, self.h.get() as u16), h: self.h }
+    }
 }
 
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Default)]
@@ -417,6 +983,7 @@ impl Thickness {
         let (l, r) = match h_align {
             HAlign::Left => (0, w),
             HAlign::Right => (w, 0),
+            HAlign::Stretch => (0, 0),
             HAlign::Center => {
                 let l = w / 2;
                 let r = w - l;
@@ -429,6 +996,7 @@ impl Thickness {
         let (t, b) = match v_align {
             VAlign::Top => (0, h),
             VAlign::Bottom => (h, 0),
+            VAlign::Stretch => (0, 0),
             VAlign::Center => {
                 let t = h / 2;
                 let b = h - t;
@@ -578,6 +1146,20 @@ impl SubAssign for Thickness {
     }
 }
 
+impl Thickness {
+    pub fn mirror_h(self) -> Thickness {
+        Thickness { l: self.r, t: self.t, r: self.l, b: self.b }
+    }
+
+    /// Treats `l` and `r` as start and end sides and converts them to physical ones.
+    pub fn resolve(self, flow: FlowDirection) -> Thickness {
+        match flow {
+            FlowDirection::LeftToRight => self,
+            FlowDirection::RightToLeft => self.mirror_h(),
+        }
+    }
+}
+
 impl Neg for Thickness {
     type Output = Self;
 
@@ -613,75 +1195,250 @@ impl IndexMut<Side> for Thickness {
 macro_attr! {
     #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
     #[derive(EnumDisplay!, EnumFromStr!)]
-    pub enum HAlign { Left, Center, Right }
+    pub enum HAlign { Left, Center, Right, Stretch }
 }
 
 macro_attr! {
     #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
     #[derive(EnumDisplay!, EnumFromStr!)]
-    pub enum VAlign { Top, Center, Bottom }
+    pub enum VAlign { Top, Center, Bottom, Stretch }
 }
 
-pub struct RectPoints {
-    rect: Rect,
-    x: i16,
+macro_attr! {
+    #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
+    #[derive(EnumDisplay!, EnumFromStr!)]
+    pub enum FlowDirection { LeftToRight, RightToLeft }
 }
 
-impl Iterator for RectPoints {
-    type Item = Point;
+macro_attr! {
+    #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
+    #[derive(EnumDisplay!, EnumFromStr!)]
+    pub enum LogicalHAlign { Start, Center, End, Stretch }
+}
 
-    fn next(&mut self) -> Option<Point> {
-        if self.rect.is_empty() {
-            return None;
+impl LogicalHAlign {
+    pub fn resolve(self, flow: FlowDirection) -> HAlign {
+        match (self, flow) {
+            (LogicalHAlign::Start, FlowDirection::LeftToRight) => HAlign::Left,
+            (LogicalHAlign::Start, FlowDirection::RightToLeft) => HAlign::Right,
+            (LogicalHAlign::End, FlowDirection::LeftToRight) => HAlign::Right,
+            (LogicalHAlign::End, FlowDirection::RightToLeft) => HAlign::Left,
+            (LogicalHAlign::Center, _) => HAlign::Center,
+            (LogicalHAlign::Stretch, _) => HAlign::Stretch,
         }
-        let item = Point { x: self.x, y: self.rect.t() };
-        self.x = self.x.wrapping_add(1);
-        if self.x == self.rect.r() {
-            self.x = self.rect.l();
-            self.rect.tl = Point { x: self.x, y: self.rect.t().wrapping_add(1) };
-            self.rect.size = Vector { x: self.rect.w(), y: self.rect.h().wrapping_sub(1) };
-        }
-        Some(item)
     }
+}
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let len = self.rect.area() - (self.x.wrapping_sub(self.rect.l()) as u16 as u32);
-        if len as usize as u32 == len {
-            (len as usize, Some(len as usize))
-        } else {
-            (usize::MAX, None)
+macro_attr! {
+    #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
+    #[derive(EnumDisplay!, EnumFromStr!)]
+    pub enum LogicalSide {
+        Start,
+        Top,
+        End,
+        Bottom
+    }
+}
+
+impl LogicalSide {
+    pub fn resolve(self, flow: FlowDirection) -> Side {
+        match (self, flow) {
+            (LogicalSide::Start, FlowDirection::LeftToRight) => Side::Left,
+            (LogicalSide::Start, FlowDirection::RightToLeft) => Side::Right,
+            (LogicalSide::End, FlowDirection::LeftToRight) => Side::Right,
+            (LogicalSide::End, FlowDirection::RightToLeft) => Side::Left,
+            (LogicalSide::Top, _) => Side::Top,
+            (LogicalSide::Bottom, _) => Side::Bottom,
         }
     }
+}
+
+#[derive(Debug, Clone)]
+pub struct RectPoints {
+    rect: Rect,
+    front: u32,
+    back: u32,
+}
+
+impl RectPoints {
+    fn point(&self, index: u32) -> Point {
+        let w = self.rect.w() as u16 as u32;
+        Point {
+            x: self.rect.l().wrapping_add((index % w) as u16 as i16),
+            y: self.rect.t().wrapping_add((index / w) as u16 as i16)
+        }
+    }
+}
+
+impl Iterator for RectPoints {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        if self.front == self.back {
+            return None;
+        }
+        let item = self.point(self.front);
+        self.front += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { exact_size_hint(self.back - self.front) }
 
     fn count(self) -> usize { self.size_hint().1.unwrap() }
 
     fn last(self) -> Option<Point> {
-        if self.rect.is_empty() { None } else { Some(self.rect.br_inner()) }
+        if self.front == self.back { None } else { Some(self.point(self.back - 1)) }
     }
 
     fn advance_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
         if let Some(rem) = self.size_hint().1.and_then(|len| n.checked_sub(len)).and_then(NonZeroUsize::new) {
-            self.x = self.rect.l();
-            self.rect.tl = self.rect.bl();
+            self.front = self.back;
+            return Err(rem);
+        }
+        self.front += n as u32;
+        Ok(())
+    }
+}
+
+impl DoubleEndedIterator for RectPoints {
+    fn next_back(&mut self) -> Option<Point> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.point(self.back))
+    }
+
+    fn advance_back_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        if let Some(rem) = self.size_hint().1.and_then(|len| n.checked_sub(len)).and_then(NonZeroUsize::new) {
+            self.back = self.front;
             return Err(rem);
         }
-        let n = n as u32;
-        let current_line_last = self.rect.r().wrapping_sub(self.x) as u16 as u32;
-        if n < current_line_last {
-            self.x = self.x.wrapping_add(n as u16 as i16);
-            return Ok(());
-        }
-        let n = n - current_line_last;
-        let skip_lines = 1i16.wrapping_add((n / self.rect.w() as u32) as u16 as i16);
-        self.rect.tl = Point { x: self.rect.l(), y: self.rect.t().wrapping_add(skip_lines) };
-        self.rect.size = Vector { x: self.rect.w(), y: self.rect.h().wrapping_sub(skip_lines) };
-        self.x = (n % self.rect.w() as u32) as u16 as i16;
+        self.back -= n as u32;
         Ok(())
     }
 }
 
+#[cfg(not(target_pointer_width="16"))]
+impl ExactSizeIterator for RectPoints { }
+
 impl FusedIterator for RectPoints { }
 
+/// Yields `(y, h_range)` for every rect row.
+#[derive(Debug, Clone)]
+pub struct RectRows {
+    ys: Range1d,
+    h_range: Range1d,
+}
+
+impl Iterator for RectRows {
+    type Item = (i16, Range1d);
+
+    fn next(&mut self) -> Option<(i16, Range1d)> { self.ys.next().map(|y| (y, self.h_range)) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.ys.size_hint() }
+}
+
+impl DoubleEndedIterator for RectRows {
+    fn next_back(&mut self) -> Option<(i16, Range1d)> { self.ys.next_back().map(|y| (y, self.h_range)) }
+}
+
+impl ExactSizeIterator for RectRows { }
+
+impl FusedIterator for RectRows { }
+
+/// Yields `(x, v_range)` for every rect column.
+#[derive(Debug, Clone)]
+pub struct RectColumns {
+    xs: Range1d,
+    v_range: Range1d,
+}
+
+impl Iterator for RectColumns {
+    type Item = (i16, Range1d);
+
+    fn next(&mut self) -> Option<(i16, Range1d)> { self.xs.next().map(|x| (x, self.v_range)) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.xs.size_hint() }
+}
+
+impl DoubleEndedIterator for RectColumns {
+    fn next_back(&mut self) -> Option<(i16, Range1d)> { self.xs.next_back().map(|x| (x, self.v_range)) }
+}
+
+impl ExactSizeIterator for RectColumns { }
+
+impl FusedIterator for RectColumns { }
+
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
+pub struct Tile {
+    pub chunk: Point,
+    pub rect: Rect,
+}
+
+/// Yields rect tiles row by row, see [`Rect::tiles_aligned`].
+#[derive(Debug, Clone)]
+pub struct Tiles {
+    rect: Rect,
+    tile: Vector,
+    /// The first tile part outside `rect`, components interpreted as `u16`.
+    lead: Vector,
+    first_chunk: Point,
+    columns: u32,
+    front: u32,
+    back: u32,
+}
+
+impl Tiles {
+    fn tile(&self, index: u32) -> Tile {
+        let (i, j) = (index % self.columns, index / self.columns);
+        let clip = |lead: i16, step: i16, len: i16, n: u32| {
+            let from = n as i32 * step as u16 as i32 - lead as u16 as i32;
+            let to = (from + step as u16 as i32).min(len as u16 as i32);
+            (from.max(0), to)
+        };
+        let (l, r) = clip(self.lead.x, self.tile.x, self.rect.w(), i);
+        let (t, b) = clip(self.lead.y, self.tile.y, self.rect.h(), j);
+        Tile {
+            chunk: self.first_chunk.offset(Vector { x: i as u16 as i16, y: j as u16 as i16 }),
+            rect: Rect {
+                tl: self.rect.tl.offset(Vector { x: l as u16 as i16, y: t as u16 as i16 }),
+                size: Vector { x: (r - l) as u16 as i16, y: (b - t) as u16 as i16 }
+            }
+        }
+    }
+}
+
+impl Iterator for Tiles {
+    type Item = Tile;
+
+    fn next(&mut self) -> Option<Tile> {
+        if self.front == self.back {
+            return None;
+        }
+        let item = self.tile(self.front);
+        self.front += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { exact_size_hint(self.back - self.front) }
+}
+
+impl DoubleEndedIterator for Tiles {
+    fn next_back(&mut self) -> Option<Tile> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.tile(self.back))
+    }
+}
+
+#[cfg(not(target_pointer_width="16"))]
+impl ExactSizeIterator for Tiles { }
+
+impl FusedIterator for Tiles { }
+
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
 pub struct Rect {
     pub tl: Point,
@@ -689,6 +1446,18 @@ pub struct Rect {
 }
 
 impl Rect {
+    /// Returns an error instead of a rect wrapping around `i16::MAX`.
+    pub fn try_new(tl: Point, size: Vector) -> Result<Rect, GeometryError> {
+        checked_end(tl.x, size.x as u16, Side::Right)?;
+        checked_end(tl.y, size.y as u16, Side::Bottom)?;
+        Ok(Rect { tl, size })
+    }
+
+    /// Moves `tl` back so the rect does not wrap around `i16::MAX`. The size is kept.
+    pub fn saturating_new(tl: Point, size: Vector) -> Rect {
+        Rect { tl, size }.saturating_offset(Vector::null())
+    }
+
     pub fn from_tl_br(tl: Point, br: Point) -> Rect {
         Rect { tl, size: br.offset_from(tl) }
     }
@@ -714,6 +1483,10 @@ impl Rect {
 
     pub fn b(self) -> i16 { self.tl.y.wrapping_add(self.size.y) }
 
+    pub fn checked_r(self) -> Option<i16> { checked_end(self.l(), self.w() as u16, Side::Right).ok() }
+
+    pub fn checked_b(self) -> Option<i16> { checked_end(self.t(), self.h() as u16, Side::Bottom).ok() }
+
     pub fn tr(self) -> Point { Point { x: self.r(), y: self.t() } }
 
     pub fn bl(self) -> Point { Point { x: self.l(), y: self.b() } }
@@ -736,7 +1509,34 @@ impl Rect {
 
     pub fn area(self) -> u32 { self.size.rect_area() }
 
-    pub fn points(self) -> RectPoints { RectPoints { rect: self, x: self.l() } }
+    pub fn points(self) -> RectPoints { RectPoints { rect: self, front: 0, back: self.area() } }
+
+    pub fn points_column_major(self) -> ColumnMajorPoints { ColumnMajorPoints::new(self) }
+
+    /// Reverse row-major order: bottom to top, right to left.
+    pub fn points_rev(self) -> Rev<RectPoints> { self.points().rev() }
+
+    pub fn points_serpentine(self) -> SerpentinePoints { SerpentinePoints::new(self) }
+
+    pub fn points_spiral(self) -> SpiralPoints { SpiralPoints::new(self) }
+
+    pub fn points_rings(self) -> RingPoints { RingPoints::new(self) }
+
+    /// Visits rect points in increasing [`Point::to_morton`] order.
+    pub fn points_z_order(self) -> ZOrderPoints { ZOrderPoints::new(self) }
+
+    /// Visits rect points in increasing [`Point::to_hilbert`] order for the whole plane curve.
+    pub fn points_hilbert(self) -> HilbertPoints { HilbertPoints::new(self) }
+
+    pub fn rows(self) -> RectRows {
+        let ys = if self.is_empty() { Range1d::new(self.t(), self.t()) } else { self.v_range() };
+        RectRows { ys, h_range: self.h_range() }
+    }
+
+    pub fn columns(self) -> RectColumns {
+        let xs = if self.is_empty() { Range1d::new(self.l(), self.l()) } else { self.h_range() };
+        RectColumns { xs, v_range: self.v_range() }
+    }
 
     pub fn h_range(self) -> Range1d { Range1d { start: self.l(), end: self.r() } }
 
@@ -746,6 +1546,10 @@ impl Rect {
         self.h_range().contains(p.x) && self.v_range().contains(p.y)
     }
 
+    pub fn align_in(container: Rect, size: Vector, h_align: HAlign, v_align: VAlign) -> Rect {
+        Thickness::align(size, container.size, h_align, v_align).shrink_rect(container)
+    }
+
     pub fn intersect(self, other: Rect) -> Rect {
         let h = self.h_range().intersect(other.h_range());
         let v = self.v_range().intersect(other.v_range());
@@ -801,6 +1605,93 @@ impl Rect {
         Rect { tl: self.tl.offset(d), size: self.size }
     }
 
+    pub fn checked_offset(self, d: Vector) -> Option<Rect> {
+        Rect::try_new(self.tl.checked_offset(d)?, self.size).ok()
+    }
+
+    /// The smallest rect with edges on the grid of `step` sized cells with a node at `origin`
+    /// containing `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any `step` component is zero.
+    pub fn snap_outward(self, step: Vector, origin: Point) -> Rect {
+        Rect::from_h_v_ranges(
+            self.h_range().snap_outward(step.x as u16, origin.x),
+            self.v_range().snap_outward(step.y as u16, origin.y)
+        )
+    }
+
+    /// The largest rect with edges on the grid of `step` sized cells with a node at `origin`
+    /// contained in `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any `step` component is zero.
+    pub fn snap_inward(self, step: Vector, origin: Point) -> Rect {
+        Rect::from_h_v_ranges(
+            self.h_range().snap_inward(step.x as u16, origin.x),
+            self.v_range().snap_inward(step.y as u16, origin.y)
+        )
+    }
+
+    /// The rect of coordinates of the chunks overlapped by `self`, see [`Point::chunk`]
+    /// and [`Rect::tiles_aligned`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any `tile` component is zero.
+    pub fn chunks(self, tile: Vector, origin: Point) -> Rect {
+        let tiles = self.tiles_aligned(tile, origin);
+        let rows = tiles.back / tiles.columns.max(1);
+        Rect { tl: tiles.first_chunk, size: Vector { x: tiles.columns as u16 as i16, y: rows as u16 as i16 } }
+    }
+
+    /// Splits `self` into `tile` sized parts aligned to `self.tl`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any `tile` component is zero.
+    pub fn tiles(self, tile: Vector) -> Tiles { self.tiles_aligned(tile, self.tl) }
+
+    /// Splits `self` into `tile` sized chunks aligned to `origin`, clipped to `self`.
+    ///
+    /// If `self` wraps around the `i16` plane, the tiles past the wrap continue the grid
+    /// of the preceding ones, so their chunk coordinates differ from [`Point::chunk`] there.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any `tile` component is zero.
+    pub fn tiles_aligned(self, tile: Vector, origin: Point) -> Tiles {
+        let first_chunk = self.tl.chunk(tile, origin);
+        let lead = Vector {
+            x: (self.l() as i32 - snap_floor(self.l() as i32, tile.x as u16, origin.x)) as u16 as i16,
+            y: (self.t() as i32 - snap_floor(self.t() as i32, tile.y as u16, origin.y)) as u16 as i16,
+        };
+        let count = |len: i16, lead: i16, step: i16| {
+            (len as u16 as u32 + lead as u16 as u32).div_ceil(step as u16 as u32)
+        };
+        let (columns, rows) = if self.is_empty() {
+            (0, 0)
+        } else {
+            (count(self.w(), lead.x, tile.x), count(self.h(), lead.y, tile.y))
+        };
+        Tiles { rect: self, tile, lead, first_chunk, columns, front: 0, back: columns * rows }
+    }
+
+    /// Moves the rect as far as possible without wrapping. The size is kept.
+    pub fn saturating_offset(self, d: Vector) -> Rect {
+        self.saturating_offset_wide(d.x as i32, d.y as i32)
+    }
+
+    fn saturating_offset_wide(self, dx: i32, dy: i32) -> Rect {
+        let tl = Point {
+            x: saturating_start(self.l(), dx, self.w() as u16),
+            y: saturating_start(self.t(), dy, self.h() as u16),
+        };
+        Rect { tl, size: self.size }
+    }
+
     pub fn relative_to(self, base: Point) -> Rect {
         Rect { tl: self.tl.relative_to(base), size: self.size }
     }
@@ -809,6 +1700,22 @@ impl Rect {
         Rect { tl: self.tl.absolute_with(base), size: self.size }
     }
 
+    pub fn checked_relative_to(self, base: Point) -> Option<Rect> {
+        Rect::try_new(self.tl.checked_relative_to(base)?, self.size).ok()
+    }
+
+    pub fn saturating_relative_to(self, base: Point) -> Rect {
+        self.saturating_offset_wide(-(base.x as i32), -(base.y as i32))
+    }
+
+    pub fn checked_absolute_with(self, base: Point) -> Option<Rect> {
+        Rect::try_new(self.tl.checked_absolute_with(base)?, self.size).ok()
+    }
+
+    pub fn saturating_absolute_with(self, base: Point) -> Rect {
+        self.saturating_offset_wide(base.x as i32, base.y as i32)
+    }
+
     pub fn t_line(self) -> Rect {
         let height = min(1, self.size.y as u16) as i16;
         Rect { tl: self.tl, size: Vector { x: self.size.x, y: height } }
@@ -834,6 +1741,19 @@ impl Rect {
             size: Vector { x: width, y: self.size.y }
         }
     }
+
+    pub fn mirror_h(self, container: Rect) -> Rect {
+        let l = container.l().wrapping_add(container.r().wrapping_sub(self.r()));
+        Rect { tl: Point { x: l, y: self.t() }, size: self.size }
+    }
+
+    /// Converts a rect laid out left-to-right inside `container` to the `flow` direction.
+    pub fn resolve(self, container: Rect, flow: FlowDirection) -> Rect {
+        match flow {
+            FlowDirection::LeftToRight => self,
+            FlowDirection::RightToLeft => self.mirror_h(container),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -844,6 +1764,265 @@ impl Arbitrary for Rect {
     }
 }
 
+/// A rect which never wraps around the `i16` plane: `l <= r` and `t <= b`.
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
+pub struct BoundedRect {
+    l: i16,
+    t: i16,
+    r: i16,
+    b: i16,
+}
+
+impl BoundedRect {
+    pub fn new(l: i16, t: i16, r: i16, b: i16) -> Option<BoundedRect> {
+        if l <= r && t <= b { Some(BoundedRect { l, t, r, b }) } else { None }
+    }
+
+    pub fn from_tl_br(tl: Point, br: Point) -> Option<BoundedRect> {
+        BoundedRect::new(tl.x, tl.y, br.x, br.y)
+    }
+
+    pub fn l(self) -> i16 { self.l }
+
+    pub fn t(self) -> i16 { self.t }
+
+    pub fn r(self) -> i16 { self.r }
+
+    pub fn b(self) -> i16 { self.b }
+
+    pub fn w(self) -> u16 { self.r.wrapping_sub(self.l) as u16 }
+
+    pub fn h(self) -> u16 { self.b.wrapping_sub(self.t) as u16 }
+
+    pub fn tl(self) -> Point { Point { x: self.l, y: self.t } }
+
+    pub fn br(self) -> Point { Point { x: self.r, y: self.b } }
+
+    pub fn size(self) -> Vector { self.br().offset_from(self.tl()) }
+
+    pub fn area(self) -> u32 { self.w() as u32 * self.h() as u32 }
+
+    pub fn is_empty(self) -> bool { self.l == self.r || self.t == self.b }
+
+    pub fn contains(self, p: Point) -> bool {
+        self.l <= p.x && p.x < self.r && self.t <= p.y && p.y < self.b
+    }
+
+    pub fn contains_rect(self, other: BoundedRect) -> bool {
+        other.is_empty() || self.l <= other.l && other.r <= self.r && self.t <= other.t && other.b <= self.b
+    }
+
+    pub fn intersect(self, other: BoundedRect) -> BoundedRect {
+        let l = max(self.l, other.l);
+        let t = max(self.t, other.t);
+        BoundedRect { l, t, r: max(l, min(self.r, other.r)), b: max(t, min(self.b, other.b)) }
+    }
+
+    /// The smallest rect containing both rects. Empty rects are ignored.
+    pub fn union(self, other: BoundedRect) -> BoundedRect {
+        if other.is_empty() { return self; }
+        if self.is_empty() { return other; }
+        BoundedRect {
+            l: min(self.l, other.l),
+            t: min(self.t, other.t),
+            r: max(self.r, other.r),
+            b: max(self.b, other.b),
+        }
+    }
+
+    pub fn checked_offset(self, d: Vector) -> Option<BoundedRect> {
+        Some(BoundedRect {
+            l: self.l.checked_add(d.x)?,
+            t: self.t.checked_add(d.y)?,
+            r: self.r.checked_add(d.x)?,
+            b: self.b.checked_add(d.y)?,
+        })
+    }
+}
+
+impl From<BoundedRect> for Rect {
+    fn from(r: BoundedRect) -> Rect {
+        Rect { tl: r.tl(), size: r.size() }
+    }
+}
+
+impl TryFrom<Rect> for BoundedRect {
+    type Error = GeometryError;
+
+    fn try_from(r: Rect) -> Result<BoundedRect, GeometryError> {
+        Rect::try_new(r.tl, r.size)?;
+        Ok(BoundedRect { l: r.l(), t: r.t(), r: r.r(), b: r.b() })
+    }
+}
+
+#[cfg(test)]
+impl Arbitrary for BoundedRect {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let a = <(Point, Point)>::arbitrary(g);
+        BoundedRect {
+            l: min(a.0.x, a.1.x),
+            t: min(a.0.y, a.1.y),
+            r: max(a.0.x, a.1.x),
+            b: max(a.0.y, a.1.y),
+        }
+    }
+}
+
+/// A range which never wraps around `i16::MAX`, up to the whole axis (all 65536 coordinates).
+///
+/// Unlike with [`Range1d`], the intersection of spans is always an exact span,
+/// and the union is the smallest span containing both.
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
+pub struct Span1d {
+    start: i32,
+    end: i32,
+}
+
+impl Span1d {
+    pub const fn full() -> Span1d { Span1d { start: i16::MIN as i32, end: i16::MAX as i32 + 1 } }
+
+    pub fn start(self) -> i16 { self.start as i16 }
+
+    pub fn len(self) -> u32 { (self.end - self.start) as u32 }
+
+    pub fn is_empty(self) -> bool { self.start == self.end }
+
+    pub fn is_full(self) -> bool { self.len() == 1 << 16 }
+
+    /// Returns `None` for the whole axis.
+    pub fn range(self) -> Option<Range1d> {
+        if self.is_full() { None } else { Some(Range1d::new(self.start as i16, self.end as i16)) }
+    }
+
+    pub fn contains(self, coord: i16) -> bool {
+        self.start <= coord as i32 && (coord as i32) < self.end
+    }
+
+    pub fn intersect(self, other: Span1d) -> Span1d {
+        let start = max(self.start, other.start);
+        Span1d { start, end: max(start, min(self.end, other.end)) }
+    }
+
+    /// The smallest span containing both spans. Empty spans are ignored.
+    pub fn union(self, other: Span1d) -> Span1d {
+        if other.is_empty() { return self; }
+        if self.is_empty() { return other; }
+        Span1d { start: min(self.start, other.start), end: max(self.end, other.end) }
+    }
+}
+
+impl TryFrom<Range1d> for Span1d {
+    type Error = GeometryError;
+
+    fn try_from(r: Range1d) -> Result<Span1d, GeometryError> {
+        let end = r.start as i32 + r.len() as i32;
+        if end > i16::MAX as i32 + 1 { return Err(GeometryError { bound: Side::Right }); }
+        Ok(Span1d { start: r.start as i32, end })
+    }
+}
+
+/// A rect, a band, or the whole plane, never wrapping around the `i16` plane.
+///
+/// The union of spans reaching both ends of an axis is full along it,
+/// so that [`HBand`] and [`VBand`] results come out of [`Span2d::union`] as is.
+/// [`Rect::union`] keeps its wrapping semantics and does not return spans.
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
+pub struct Span2d {
+    pub h: Span1d,
+    pub v: Span1d,
+}
+
+impl Span2d {
+    pub const fn full() -> Span2d { Span2d { h: Span1d::full(), v: Span1d::full() } }
+
+    pub fn is_empty(self) -> bool { self.h.is_empty() || self.v.is_empty() }
+
+    pub fn is_full(self) -> bool { self.h.is_full() && self.v.is_full() }
+
+    pub fn area(self) -> u64 { self.h.len() as u64 * self.v.len() as u64 }
+
+    pub fn contains(self, p: Point) -> bool {
+        self.h.contains(p.x) && self.v.contains(p.y)
+    }
+
+    pub fn intersect(self, other: Span2d) -> Span2d {
+        Span2d { h: self.h.intersect(other.h), v: self.v.intersect(other.v) }
+    }
+
+    /// The smallest span containing both spans. Empty spans are ignored.
+    pub fn union(self, other: Span2d) -> Span2d {
+        if other.is_empty() { return self; }
+        if self.is_empty() { return other; }
+        Span2d { h: self.h.union(other.h), v: self.v.union(other.v) }
+    }
+
+    pub fn rect(self) -> Option<Rect> {
+        Some(Rect::from_h_v_ranges(self.h.range()?, self.v.range()?))
+    }
+
+    /// Returns `None` if the span is not a non-empty horizontal band.
+    pub fn h_band(self) -> Option<HBand> {
+        if self.h.is_full() { HBand::from_v_range(self.v.range()?) } else { None }
+    }
+
+    /// Returns `None` if the span is not a non-empty vertical band.
+    pub fn v_band(self) -> Option<VBand> {
+        if self.v.is_full() { VBand::from_h_range(self.h.range()?) } else { None }
+    }
+}
+
+impl From<BoundedRect> for Span2d {
+    fn from(r: BoundedRect) -> Span2d {
+        Span2d {
+            h: Span1d { start: r.l() as i32, end: r.r() as i32 },
+            v: Span1d { start: r.t() as i32, end: r.b() as i32 },
+        }
+    }
+}
+
+impl TryFrom<Rect> for Span2d {
+    type Error = GeometryError;
+
+    fn try_from(r: Rect) -> Result<Span2d, GeometryError> {
+        let h = r.h_range().try_into()?;
+        let v = r.v_range().try_into().map_err(|_| GeometryError { bound: Side::Bottom })?;
+        Ok(Span2d { h, v })
+    }
+}
+
+impl TryFrom<HBand> for Span2d {
+    type Error = GeometryError;
+
+    fn try_from(b: HBand) -> Result<Span2d, GeometryError> {
+        let v = b.v_range().try_into().map_err(|_| GeometryError { bound: Side::Bottom })?;
+        Ok(Span2d { h: Span1d::full(), v })
+    }
+}
+
+impl TryFrom<VBand> for Span2d {
+    type Error = GeometryError;
+
+    fn try_from(b: VBand) -> Result<Span2d, GeometryError> {
+        Ok(Span2d { h: b.h_range().try_into()?, v: Span1d::full() })
+    }
+}
+
+#[cfg(test)]
+impl Arbitrary for Span1d {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let a = <Option<(i16, i16)>>::arbitrary(g);
+        a.map_or(Span1d::full(), |(x, y)| Span1d { start: min(x, y) as i32, end: max(x, y) as i32 + 1 })
+    }
+}
+
+#[cfg(test)]
+impl Arbitrary for Span2d {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let a = <(Span1d, Span1d)>::arbitrary(g);
+        Span2d { h: a.0, v: a.1 }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use quickcheck::TestResult;
@@ -918,6 +2097,360 @@ mod tests {
         r1.union(r2).unwrap().right().unwrap() == r1
     }
 
+    #[quickcheck]
+    fn rect_align_in_stretch(container: Rect, size: Vector) -> bool {
+        Rect::align_in(container, size, HAlign::Stretch, VAlign::Stretch) == container
+    }
+
+    #[quickcheck]
+    fn rect_align_in_size(container: Rect, size: Vector, h_align: bool, v_align: bool) -> bool {
+        let size = size.min(container.size);
+        let h_align = if h_align { HAlign::Center } else { HAlign::Right };
+        let v_align = if v_align { VAlign::Center } else { VAlign::Bottom };
+        Rect::align_in(container, size, h_align, v_align).size == size
+    }
+
+    #[test]
+    fn align_from_str() {
+        assert_eq!("Stretch".parse::<HAlign>(), Ok(HAlign::Stretch));
+        assert_eq!("Stretch".parse::<VAlign>(), Ok(VAlign::Stretch));
+    }
+
+    #[quickcheck]
+    fn rect_mirror_h_twice(r: Rect, container: Rect) -> bool {
+        r.mirror_h(container).mirror_h(container) == r
+    }
+
+    #[quickcheck]
+    fn rect_mirror_h_align(container: Rect, size: Vector) -> bool {
+        let size = size.min(container.size);
+        let l = Rect::align_in(container, size, LogicalHAlign::Start.resolve(FlowDirection::RightToLeft), VAlign::Top);
+        let r = Rect::align_in(container, size, LogicalHAlign::Start.resolve(FlowDirection::LeftToRight), VAlign::Top);
+        l.mirror_h(container) == r
+    }
+
+    #[test]
+    fn logical_thickness() {
+        let t = Thickness::new(1, 2, 3, 4);
+        assert_eq!(t.resolve(FlowDirection::RightToLeft)[LogicalSide::Start.resolve(FlowDirection::RightToLeft)], 1);
+        assert_eq!(t.mirror_h(), Thickness::new(3, 2, 1, 4));
+    }
+
+    #[quickcheck]
+    fn point_checked_offset(p: Point, d: Vector) -> bool {
+        p.checked_offset(d).is_none_or(|x| x == p.offset(d) && x == p.saturating_offset(d))
+    }
+
+    #[quickcheck]
+    fn vector_checked_add_sub(a: Vector, b: Vector) -> bool {
+        a.checked_add(b).is_none_or(|x| x == a + b && x == a.saturating_add(b)) &&
+        a.checked_sub(b).is_none_or(|x| x == a - b && x == a.saturating_sub(b))
+    }
+
+    #[quickcheck]
+    fn rect_checked_offset(r: Rect, d: Vector) -> bool {
+        r.checked_offset(d).is_none_or(|x| x == r.offset(d) && x == r.saturating_offset(d))
+    }
+
+    #[quickcheck]
+    fn rect_saturating_offset_does_not_wrap(r: Rect, d: Vector) -> bool {
+        let r = r.saturating_offset(d);
+        r.checked_r().is_some() && r.checked_b().is_some()
+    }
+
+    #[quickcheck]
+    fn range_checked_offset(start: i16, end: i16, d: i16) -> bool {
+        let r = Range1d::new(start, end);
+        r.checked_offset(d).is_none_or(|x| x == r.offset(d) && x == r.saturating_offset(d))
+    }
+
+    #[quickcheck]
+    fn rect_checked_relative_absolute(r: Rect, base: Point) -> bool {
+        r.checked_relative_to(base).is_none_or(|x| x == r.relative_to(base) && x == r.saturating_relative_to(base)) &&
+        r.checked_absolute_with(base).is_none_or(|x| x == r.absolute_with(base) && x == r.saturating_absolute_with(base))
+    }
+
+    #[quickcheck]
+    fn band_checked_relative_absolute(l: i16, w: i16, base: Point) -> bool {
+        let Some(w) = NonZeroI16::new(w) else { return true };
+        let v = VBand { l, w };
+        let h = HBand { t: l, h: w };
+        v.checked_relative_to(base).is_none_or(|x| x == v.relative_to(base) && x == v.saturating_relative_to(base)) &&
+        v.checked_absolute_with(base).is_none_or(|x| x == v.absolute_with(base) && x == v.saturating_absolute_with(base)) &&
+        h.checked_relative_to(base).is_none_or(|x| x == h.relative_to(base) && x == h.saturating_relative_to(base)) &&
+        h.checked_absolute_with(base).is_none_or(|x| x == h.absolute_with(base) && x == h.saturating_absolute_with(base))
+    }
+
+    #[quickcheck]
+    fn saturating_new_does_not_wrap(tl: Point, size: Vector, w: i16) -> bool {
+        let Some(w) = NonZeroI16::new(w) else { return true };
+        let r = Rect::saturating_new(tl, size);
+        let range = Range1d::saturating_new(tl.x, tl.x.wrapping_add(size.x));
+        r.size == size && Rect::try_new(r.tl, r.size).is_ok() &&
+        range.len() == size.x as u16 && range.checked_len().is_some() &&
+        VBand::try_new(VBand::saturating_new(tl.x, w).l, w).is_ok() &&
+        HBand::try_new(HBand::saturating_new(tl.y, w).t, w).is_ok()
+    }
+
+    #[test]
+    fn geometry_error_bound() {
+        let size = Vector { x: 10, y: 10 };
+        assert_eq!(Rect::try_new(Point { x: i16::MAX - 5, y: 0 }, size), Err(GeometryError { bound: Side::Right }));
+        assert_eq!(Rect::try_new(Point { x: 0, y: i16::MAX - 5 }, size), Err(GeometryError { bound: Side::Bottom }));
+        assert!(Rect::try_new(Point { x: i16::MAX - 10, y: 0 }, size).is_ok());
+        let h = NonZeroI16::new(3).unwrap();
+        assert_eq!(HBand::try_new(i16::MAX - 1, h), Err(GeometryError { bound: Side::Bottom }));
+        assert_eq!(Range1d::try_new(5, 4), Err(GeometryError { bound: Side::Right }));
+        assert_eq!(Point { x: i16::MAX, y: 0 }.checked_offset(Vector { x: 1, y: 0 }), None);
+        let r = Rect { tl: Point { x: 0, y: 0 }, size };
+        let base = Point { x: i16::MIN, y: 0 };
+        assert_eq!(r.checked_relative_to(base), None);
+        assert_eq!(r.saturating_relative_to(base).tl, Point { x: i16::MAX - 10, y: 0 });
+        assert_eq!(Rect::saturating_new(Point { x: i16::MAX, y: 0 }, size).l(), i16::MAX - 10);
+        assert_eq!(HBand::saturating_new(i16::MAX - 1, h).t, i16::MAX - 3);
+    }
+
+    #[quickcheck]
+    fn bounded_rect_union_contains(r1: BoundedRect, r2: BoundedRect, p: Point) -> bool {
+        let u = r1.union(r2);
+        u.contains_rect(r1) && u.contains_rect(r2) && (u.contains(p) || !(r1.contains(p) || r2.contains(p)))
+    }
+
+    #[quickcheck]
+    fn bounded_rect_intersect_contains(r1: BoundedRect, r2: BoundedRect, p: Point) -> bool {
+        r1.intersect(r2).contains(p) == (r1.contains(p) && r2.contains(p))
+    }
+
+    #[quickcheck]
+    fn bounded_rect_to_rect(r: BoundedRect, p: Point) -> bool {
+        let rect = Rect::from(r);
+        BoundedRect::try_from(rect) == Ok(r) && rect.contains(p) == r.contains(p)
+    }
+
+    #[quickcheck]
+    fn rect_to_bounded_rect(r: Rect) -> bool {
+        BoundedRect::try_from(r).is_ok() == Rect::try_new(r.tl, r.size).is_ok()
+    }
+
+    #[quickcheck]
+    fn span_union_contains(s1: Span2d, s2: Span2d, p: Point) -> bool {
+        s1.union(s2).contains(p) || !(s1.contains(p) || s2.contains(p))
+    }
+
+    #[quickcheck]
+    fn span_intersect_contains(s1: Span2d, s2: Span2d, p: Point) -> bool {
+        s1.intersect(s2).contains(p) == (s1.contains(p) && s2.contains(p))
+    }
+
+    #[quickcheck]
+    fn span_agrees_with_bounded_rect(r1: BoundedRect, r2: BoundedRect) -> bool {
+        let (s1, s2) = (Span2d::from(r1), Span2d::from(r2));
+        s1.union(s2) == Span2d::from(r1.union(r2)) && s1.intersect(s2) == Span2d::from(r1.intersect(r2)) &&
+            Span2d::try_from(Rect::from(r1)) == Ok(s1)
+    }
+
+    #[test]
+    fn span_full_axis() {
+        let a = Span1d::try_from(Range1d::new(0, i16::MIN)).unwrap();
+        let b = Span1d::try_from(Range1d::new(i16::MIN, 0)).unwrap();
+        assert_eq!(a.union(b), Span1d::full());
+        assert_eq!(Span1d::full().len(), 65536);
+        assert_eq!(Span1d::full().range(), None);
+        assert_eq!(Span2d::full().area(), 1 << 32);
+        assert!(Span1d::try_from(Range1d::new(0, -100)).is_err());
+        let left = Rect { tl: Point { x: i16::MIN, y: 3 }, size: Vector { x: 10, y: 2 } };
+        let right = Rect { tl: Point { x: i16::MAX - 9, y: 4 }, size: Vector { x: 10, y: 2 } };
+        let band = Span2d::try_from(left).unwrap().union(Span2d::try_from(right).unwrap());
+        assert_eq!(band.h_band(), HBand::from_t_b(3, 6));
+        assert!(Span2d::try_from(HBand::from_t_b(3, 6).unwrap()).unwrap().contains(Point { x: i16::MAX, y: 5 }));
+    }
+
+    #[quickcheck]
+    fn vector_checked_mul_div(v: Vector, k: i16) -> bool {
+        v.checked_mul(k).is_none_or(|x| x == v * k && x == v.saturating_mul(k)) &&
+        v.checked_div(k).is_none_or(|x| x == v / k && x == v.saturating_div(k)) &&
+        v.checked_rem(k).is_none_or(|x| x == v % k)
+    }
+
+    #[quickcheck]
+    fn vector_euclid(v: Vector, k: i16) -> bool {
+        if k == 0 { return true; }
+        let q = v.div_euclid(k);
+        let r = v.rem_euclid(k);
+        q * k + r == v && r.x >= 0 && r.y >= 0
+    }
+
+    #[quickcheck]
+    fn vector_perp(v: Vector) -> bool {
+        v.perp_cw().perp_ccw() == v && v.perp_cw().perp_cw() == -v &&
+        v.checked_perp_cw().is_none_or(|x| x == v.saturating_perp_cw() && v.dot(x) == 0)
+    }
+
+    #[quickcheck]
+    fn vector_cross_dot(a: Vector, b: Vector) -> bool {
+        a.cross(b) == -b.cross(a) && a.dot(b) == b.dot(a) &&
+        a.checked_dot(b).is_none_or(|x| x == a.saturating_dot(b))
+    }
+
+    #[test]
+    fn vector_arithmetic() {
+        let v = Vector { x: -7, y: 3 };
+        assert_eq!(v.div_euclid(2), Vector { x: -4, y: 1 });
+        assert_eq!(v.rem_euclid(2), Vector { x: 1, y: 1 });
+        assert_eq!(v.abs(), Vector { x: 7, y: 3 });
+        assert_eq!(v.signum(), Vector { x: -1, y: 1 });
+        assert_eq!(Vector { x: 1, y: 0 }.perp_cw(), Vector { x: 0, y: 1 });
+        assert_eq!(v.component_mul(Vector { x: 2, y: -1 }), Vector { x: -14, y: -3 });
+        assert_eq!([v, v, v].iter().sum::<Vector>(), v * 3);
+        let min = Vector { x: i16::MIN, y: i16::MIN };
+        assert_eq!(min.checked_dot(min), None);
+        assert_eq!(min.saturating_dot(min), i32::MAX);
+        assert_eq!(min.cross(Vector { x: i16::MAX, y: i16::MIN }), i16::MIN as i32 * i16::MIN as i32 - i16::MIN as i32 * i16::MAX as i32);
+    }
+
+    #[test]
+    fn snap_negative() {
+        let step = Vector { x: 4, y: 3 };
+        let origin = Point { x: 1, y: 0 };
+        assert_eq!(Point { x: -1, y: -1 }.snap_to(step, origin), Point { x: -3, y: -3 });
+        assert_eq!(Range1d::new(-5, -2).snap_outward(4, 0), Range1d::new(-8, 0));
+        assert_eq!(Range1d::new(-5, -2).snap_inward(4, 0), Range1d::new(-4, -4));
+        let r = Rect { tl: Point { x: -5, y: -5 }, size: Vector { x: 10, y: 7 } };
+        assert_eq!(r.snap_outward(step, origin), Rect { tl: Point { x: -7, y: -6 }, size: Vector { x: 12, y: 9 } });
+        assert_eq!(r.snap_inward(step, origin), Rect { tl: Point { x: -3, y: -3 }, size: Vector { x: 8, y: 3 } });
+    }
+
+    #[quickcheck]
+    fn rect_snap_bounds(tl: (i8, i8), size: (u8, u8), step: (u8, u8), origin: (i8, i8)) -> bool {
+        let r = Rect { tl: Point { x: tl.0 as i16, y: tl.1 as i16 }, size: Vector { x: size.0 as i16, y: size.1 as i16 } };
+        let step = Vector { x: step.0 as i16 + 1, y: step.1 as i16 + 1 };
+        let origin = Point { x: origin.0 as i16, y: origin.1 as i16 };
+        let outer = r.snap_outward(step, origin);
+        let inner = r.snap_inward(step, origin);
+        let on_grid = |p: Point| p.snap_to(step, origin) == p;
+        on_grid(outer.tl) && on_grid(outer.br()) && on_grid(inner.tl) &&
+        (r.is_empty() || outer.intersect(r) == r) &&
+        (inner.is_empty() || r.intersect(inner) == inner) &&
+        outer.snap_outward(step, origin) == outer
+    }
+
+    #[quickcheck]
+    fn morton_round_trip(p: Point, q: Point) -> bool {
+        Point::from_morton(p.to_morton()) == p &&
+            (p.x <= q.x && p.y <= q.y) <= (p.to_morton() <= q.to_morton())
+    }
+
+    #[quickcheck]
+    fn hilbert_round_trip(p: Point, order: u8) -> bool {
+        let order = order % 17;
+        let d = p.to_hilbert(order);
+        let q = Point::from_hilbert(d, order);
+        let adjacent = d == 0 || {
+            let step = q.offset_from(Point::from_hilbert(d - 1, order));
+            (step.x as i32).abs() + (step.y as i32).abs() == 1
+        };
+        q.to_hilbert(order) == d && adjacent &&
+            (order == 16 || q.offset_from(p).x as i32 % (1 << order) == 0 && q.offset_from(p).y as i32 % (1 << order) == 0) &&
+            (order != 16 || q == p)
+    }
+
+    #[test]
+    fn curve_indices() {
+        let p = |x, y| Point { x, y };
+        assert_eq!([0, 1, 2, 3].map(|d| Point::from_hilbert(d, 1)), [p(0, 0), p(0, 1), p(1, 1), p(1, 0)]);
+        assert_eq!(p(5, 6).to_hilbert(0), 0);
+        assert_eq!(p(i16::MIN, i16::MIN).to_morton(), 0);
+        assert_eq!(p(0, i16::MIN).to_morton(), 1 << 30);
+        assert_eq!(p(-1, 0).to_morton(), 0x9555_5555);
+        assert_eq!(Point::from_morton(u32::MAX), p(i16::MAX, i16::MAX));
+    }
+
+    #[quickcheck]
+    fn tiles_partition_rect(r: Rect, tile: (u8, u8), origin: Point) -> TestResult {
+        if r.area() > 2000 { return TestResult::discard(); }
+        let tile = Vector { x: tile.0 as i16 % 9 + 1, y: tile.1 as i16 % 9 + 1 };
+        let tiles = r.tiles_aligned(tile, origin);
+        let chunks = r.chunks(tile, origin);
+        let area = tiles.clone().map(|t| t.rect.area()).sum::<u32>();
+        let wraps = Rect::try_new(r.tl, r.size).is_err();
+        TestResult::from_bool(
+            area == r.area() && tiles.len() as u32 == chunks.area() &&
+            tiles.clone().zip(chunks.points()).all(|(t, c)|
+                t.chunk == c && !t.rect.is_empty() &&
+                t.rect.points().all(|p| r.contains(p) && (wraps || p.chunk(tile, origin) == c))
+            ) &&
+            r.tiles(tile).next().is_none_or(|t| t.chunk == Point { x: 0, y: 0 } && t.rect.tl == r.tl)
+        )
+    }
+
+    #[test]
+    fn tiles() {
+        let r = Rect { tl: Point { x: -3, y: 2 }, size: Vector { x: 10, y: 3 } };
+        let tile = Vector { x: 4, y: 4 };
+        let origin = Point { x: 0, y: 0 };
+        assert_eq!(Point { x: -1, y: 4 }.chunk(tile, origin), Point { x: -1, y: 1 });
+        assert_eq!(r.chunks(tile, origin), Rect { tl: Point { x: -1, y: 0 }, size: Vector { x: 3, y: 2 } });
+        assert_eq!(r.tiles_aligned(tile, origin).next(), Some(Tile {
+            chunk: Point { x: -1, y: 0 },
+            rect: Rect { tl: Point { x: -3, y: 2 }, size: Vector { x: 3, y: 2 } }
+        }));
+        assert_eq!(r.tiles_aligned(tile, origin).next_back(), Some(Tile {
+            chunk: Point { x: 1, y: 1 },
+            rect: Rect { tl: Point { x: 4, y: 4 }, size: Vector { x: 3, y: 1 } }
+        }));
+        assert_eq!(r.tiles(tile).len(), 3);
+    }
+
+    #[test]
+    fn range_iterator_back() {
+        let mut r = Range1d::new(3, 6);
+        assert_eq!(r.last(), Some(5));
+        assert_eq!(r.next_back(), Some(5));
+        assert_eq!(r, Range1d::new(3, 5));
+        let mut wrapped = Range1d::new(i16::MAX, i16::MIN + 2);
+        assert_eq!(wrapped.last(), Some(i16::MIN + 1));
+        assert_eq!(wrapped.next_back(), Some(i16::MIN + 1));
+        assert_eq!(wrapped.next_back(), Some(i16::MIN));
+        assert_eq!(wrapped.next_back(), Some(i16::MAX));
+        assert_eq!(wrapped.next_back(), None);
+        assert_eq!(wrapped.last(), None);
+    }
+
+    #[quickcheck]
+    fn range_last_is_last_yielded(start: i16, end: i16) -> bool {
+        let r = Range1d::new(start, end);
+        let mut back = r;
+        let last = back.next_back();
+        last == r.last() && last == r.fold(None, |_, x| Some(x)) &&
+            back.len() == r.len().saturating_sub(1) && last.is_none_or(|x| !back.contains(x) && r.contains(x))
+    }
+
+    #[quickcheck]
+    fn rect_points_double_ended(r: Rect, n: u8) -> TestResult {
+        if r.area() > 10000 { return TestResult::discard(); }
+        let mut points = r.points();
+        let mut front = r.points();
+        let _ = front.advance_by(n as usize);
+        let back_len = points.len().saturating_sub(n as usize);
+        let mut back = r.points().rev().skip(n as usize);
+        TestResult::from_bool(
+            points.len() == r.area() as usize &&
+            points.clone().rev().eq(r.rows().rev().flat_map(|(y, h)| h.rev().map(move |x| Point { x, y }))) &&
+            points.next_back() == r.points().last() &&
+            front.len() == back_len && back.len() == back_len &&
+            front.next() == r.points().nth(n as usize) &&
+            back.next() == r.points().rev().nth(n as usize)
+        )
+    }
+
+    #[quickcheck]
+    fn rect_rows_columns(r: Rect) -> TestResult {
+        if r.area() > 10000 { return TestResult::discard(); }
+        let rows = r.rows().flat_map(|(y, h)| h.map(move |x| Point { x, y }));
+        let columns = r.columns().map(|(_, v)| v.len() as u32).sum::<u32>();
+        TestResult::from_bool(rows.eq(r.points()) && columns == r.area() && r.rows().len() == r.rows().rev().count())
+    }
+
     #[quickcheck]
     fn rect_contains_all_self_points(r: Rect) -> TestResult {
         if r.area() > 100000 { return TestResult::discard(); }
@@ -0,0 +1,158 @@
+//! Morphological operations on masks.
+//!
+//! A structuring element is a [`BitGrid`] of offsets: `(0, 0)` is the origin.
+//! A [`Thickness`] stands for the rect element spanning from `-l` to `r`
+//! horizontally and from `-t` to `b` vertically, so that dilating a single rect
+//! gives the same as [`Thickness::expand_rect`] and eroding it gives [`Thickness::shrink_rect`].
+
+use crate::{Point, Range1d, Rect, Thickness, Vector};
+use crate::grid::BitGrid;
+
+fn check(by: Thickness) {
+    assert!(by.l() >= 0 && by.t() >= 0 && by.r() >= 0 && by.b() >= 0, "negative thickness");
+}
+
+fn full(bounds: Rect) -> BitGrid {
+    let mut grid = BitGrid::new(bounds);
+    grid.fill(bounds, true);
+    grid
+}
+
+/// Extends the mask bounds to `bounds`, which should contain them.
+fn extend(mask: &BitGrid, bounds: Rect) -> BitGrid {
+    &BitGrid::new(bounds) | mask
+}
+
+/// Sets every point within `by` from a set point. The result bounds are `by.expand_rect(mask.bounds())`.
+///
+/// # Panics
+///
+/// Panics if any `by` side is negative.
+pub fn dilate(mask: &BitGrid, by: Thickness) -> BitGrid {
+    check(by);
+    let mut res = BitGrid::new(by.expand_rect(mask.bounds()));
+    for (y, h) in mask.spans() {
+        res.fill(by.expand_rect(Rect::from_h_v_ranges(h, Range1d::new(y, y.wrapping_add(1)))), true);
+    }
+    res
+}
+
+/// Keeps the set points having all points within `by` set. The result bounds are the mask bounds.
+///
+/// # Panics
+///
+/// Panics if any `by` side is negative.
+pub fn erode(mask: &BitGrid, by: Thickness) -> BitGrid {
+    check(by);
+    let outer = by.expand_rect(mask.bounds());
+    let unset = &full(outer) ^ mask;
+    let reflected = Thickness::new(by.r(), by.b(), by.l(), by.t());
+    let spoiled = dilate(&unset, reflected);
+    &full(mask.bounds()) & &!&spoiled
+}
+
+/// Erosion followed by dilation: removes parts thinner than the `by` rect. Keeps the mask bounds.
+///
+/// # Panics
+///
+/// Panics if any `by` side is negative.
+pub fn open(mask: &BitGrid, by: Thickness) -> BitGrid {
+    &full(mask.bounds()) & &dilate(&erode(mask, by), by)
+}
+
+/// Dilation followed by erosion: fills gaps narrower than the `by` rect.
+/// The result bounds are `by.expand_rect(mask.bounds())`.
+///
+/// # Panics
+///
+/// Panics if any `by` side is negative.
+pub fn close(mask: &BitGrid, by: Thickness) -> BitGrid {
+    erode(&dilate(mask, by), by)
+}
+
+fn offsets(element: &BitGrid) -> impl Iterator<Item=Vector> + '_ {
+    element.points().map(|p| p.offset_from(Point { x: 0, y: 0 }))
+}
+
+/// Sets `p + e` for every set point `p` and every `element` offset `e`.
+pub fn dilate_with(mask: &BitGrid, element: &BitGrid) -> BitGrid {
+    let e = element.bounding_rect();
+    if e.is_empty() || mask.bounds().is_empty() { return BitGrid::new(mask.bounds()); }
+    let d = e.tl.offset_from(Point { x: 0, y: 0 });
+    let bounds = Rect {
+        tl: mask.bounds().tl.offset(d),
+        size: Vector { x: mask.bounds().w().wrapping_add(e.w() - 1), y: mask.bounds().h().wrapping_add(e.h() - 1) }
+    };
+    let mut res = BitGrid::new(bounds);
+    for d in offsets(element) {
+        res = &res | &mask.offset(d);
+    }
+    res
+}
+
+/// Keeps the points `p` such that `p + e` is set for every `element` offset `e`.
+/// The result bounds are the mask bounds.
+pub fn erode_with(mask: &BitGrid, element: &BitGrid) -> BitGrid {
+    let mut res = full(mask.bounds());
+    for d in offsets(element) {
+        res = &res & &mask.offset(-d);
+    }
+    extend(&res, mask.bounds())
+}
+
+/// Erosion followed by dilation with the same element. Keeps the mask bounds.
+pub fn open_with(mask: &BitGrid, element: &BitGrid) -> BitGrid {
+    &full(mask.bounds()) & &dilate_with(&erode_with(mask, element), element)
+}
+
+/// Dilation followed by erosion with the same element.
+pub fn close_with(mask: &BitGrid, element: &BitGrid) -> BitGrid {
+    erode_with(&dilate_with(mask, element), element)
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use crate::*;
+    use crate::grid::BitGrid;
+    use crate::morphology::*;
+
+    fn element(by: Thickness) -> BitGrid {
+        let r = Rect { tl: Point { x: -by.l() as i16, y: -by.t() as i16 }, size: Vector { x: (by.l() + by.r() + 1) as i16, y: (by.t() + by.b() + 1) as i16 } };
+        BitGrid::from_fn(r, |_| true)
+    }
+
+    #[quickcheck]
+    fn morphology_agrees_with_element(m: BitGrid, by: (u8, u8, u8, u8)) -> bool {
+        let bounds = m.bounds();
+        let by = Thickness::new((by.0 % 3) as i32, (by.1 % 3) as i32, (by.2 % 3) as i32, (by.3 % 3) as i32);
+        let e = element(by);
+        let same = |a: &BitGrid, b: &BitGrid| a.points().eq(b.points());
+        let dilated = dilate(&m, by);
+        let eroded = erode(&m, by);
+        let opened = open(&m, by);
+        let closed = close(&m, by);
+        same(&dilated, &dilate_with(&m, &e)) && same(&eroded, &erode_with(&m, &e)) &&
+        same(&opened, &open_with(&m, &e)) && same(&closed, &close_with(&m, &e)) &&
+        eroded.bounds() == bounds && opened.bounds() == bounds &&
+        (&opened & &m).count_ones() == opened.count_ones() &&
+        (&closed & &m).count_ones() == m.count_ones() &&
+        dilated.points().all(|p| by.expand_rect(bounds).contains(p))
+    }
+
+    #[test]
+    fn rect_morphology() {
+        let bounds = Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: 10, y: 10 } };
+        let r = Rect { tl: Point { x: 3, y: 3 }, size: Vector { x: 4, y: 3 } };
+        let mut m = BitGrid::new(bounds);
+        m.fill(r, true);
+        let by = Thickness::new(1, 0, 2, 1);
+        assert_eq!(dilate(&m, by).bounding_rect(), by.expand_rect(r));
+        assert_eq!(dilate(&m, by).count_ones(), by.expand_rect(r).area());
+        assert_eq!(erode(&m, by).bounding_rect(), by.shrink_rect(r));
+        let ring = &dilate(&m, Thickness::all(1)) ^ &m;
+        assert_eq!(ring.count_ones(), 6 * 5 - 4 * 3);
+        m.set(Point { x: 0, y: 0 }, true);
+        assert_eq!(open(&m, Thickness::all(1)).count_ones(), 12);
+    }
+}
@@ -0,0 +1,364 @@
+//! Static rect packing into a container.
+//!
+//! Sizes are interpreted as unsigned, as elsewhere in the crate.
+
+#[cfg(feature="alloc")]
+use alloc::vec;
+#[cfg(feature="alloc")]
+use alloc::vec::Vec;
+#[cfg(feature="alloc")]
+use core::cmp::Reverse;
+use core::cmp::max;
+#[cfg(feature="alloc")]
+use enum_derive_2018::{EnumDisplay, EnumFromStr};
+#[cfg(feature="alloc")]
+use macro_attr_2018::macro_attr;
+use crate::{Rect, Vector};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Packed {
+    pub rect: Rect,
+    /// `true` if the item was rotated by 90°, i.e. `rect.size` is the transposed item size.
+    pub rotated: bool,
+}
+
+fn dims(size: Vector) -> (u32, u32) { (size.x as u16 as u32, size.y as u16 as u32) }
+
+fn orientations(size: Vector, allow_rotation: bool) -> impl Iterator<Item=(u32, u32, bool)> {
+    let (w, h) = dims(size);
+    let rotate = allow_rotation && w != h;
+    [(w, h, false), (h, w, true)].into_iter().take(if rotate { 2 } else { 1 })
+}
+
+fn packed(container: Rect, x: u32, y: u32, w: u32, h: u32, rotated: bool) -> Packed {
+    let tl = container.tl.offset(Vector { x: x as u16 as i16, y: y as u16 as i16 });
+    Packed { rect: Rect { tl, size: Vector { x: w as u16 as i16, y: h as u16 as i16 } }, rotated }
+}
+
+/// Next-fit shelf packer. Fast, does not allocate, but wastes space on items of mixed heights.
+#[derive(Debug, Clone)]
+pub struct ShelfPacker {
+    container: Rect,
+    x: u32,
+    y: u32,
+    shelf_h: u32,
+}
+
+impl ShelfPacker {
+    pub fn new(container: Rect) -> Self {
+        ShelfPacker { container, x: 0, y: 0, shelf_h: 0 }
+    }
+
+    pub fn container(&self) -> Rect { self.container }
+
+    pub fn insert(&mut self, size: Vector, allow_rotation: bool) -> Option<Packed> {
+        let (cw, ch) = dims(self.container.size);
+        let mut variants = [None, None];
+        for (v, o) in variants.iter_mut().zip(orientations(size, allow_rotation)) {
+            *v = Some(o);
+        }
+        if allow_rotation && variants[1].is_some_and(|(w, h, _)| h < w) {
+            variants.swap(0, 1);
+        }
+        for &(w, h, rotated) in variants.iter().flatten() {
+            if self.x + w <= cw && self.y + h <= ch {
+                let res = packed(self.container, self.x, self.y, w, h, rotated);
+                self.x += w;
+                self.shelf_h = max(self.shelf_h, h);
+                return Some(res);
+            }
+        }
+        for &(w, h, rotated) in variants.iter().flatten() {
+            let y = self.y + self.shelf_h;
+            if w <= cw && y + h <= ch {
+                let res = packed(self.container, 0, y, w, h, rotated);
+                self.x = w;
+                self.y = y;
+                self.shelf_h = h;
+                return Some(res);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(feature="alloc")]
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    x: u32,
+    w: u32,
+    y: u32,
+}
+
+/// Bottom-left skyline packer.
+#[cfg(feature="alloc")]
+#[derive(Debug, Clone)]
+pub struct SkylinePacker {
+    container: Rect,
+    skyline: Vec<Segment>,
+}
+
+#[cfg(feature="alloc")]
+impl SkylinePacker {
+    pub fn new(container: Rect) -> Self {
+        let skyline = vec![Segment { x: 0, w: dims(container.size).0, y: 0 }];
+        SkylinePacker { container, skyline }
+    }
+
+    pub fn container(&self) -> Rect { self.container }
+
+    fn fit(&self, i: usize, w: u32, h: u32) -> Option<u32> {
+        let (cw, ch) = dims(self.container.size);
+        let x = self.skyline[i].x;
+        if x + w > cw { return None; }
+        let mut y = 0;
+        for s in &self.skyline[i ..] {
+            if s.x >= x + w && s.x != x { break; }
+            y = max(y, s.y);
+        }
+        if y + h > ch { None } else { Some(y) }
+    }
+
+    pub fn insert(&mut self, size: Vector, allow_rotation: bool) -> Option<Packed> {
+        let mut best: Option<(u32, u32, usize, u32, u32, u32, bool)> = None;
+        for (w, h, rotated) in orientations(size, allow_rotation) {
+            for i in 0 .. self.skyline.len() {
+                let Some(y) = self.fit(i, w, h) else { continue; };
+                let x = self.skyline[i].x;
+                if best.is_none_or(|b| (y + h, x) < (b.0, b.1)) {
+                    best = Some((y + h, x, i, y, w, h, rotated));
+                }
+            }
+        }
+        let (_, x, i, y, w, h, rotated) = best?;
+        if w != 0 {
+            self.place(i, x, w, y + h);
+        }
+        Some(packed(self.container, x, y, w, h, rotated))
+    }
+
+    fn place(&mut self, i: usize, x: u32, w: u32, top: u32) {
+        self.skyline.insert(i, Segment { x, w, y: top });
+        let end = x + w;
+        let j = i + 1;
+        while j < self.skyline.len() && self.skyline[j].x < end {
+            let s = &mut self.skyline[j];
+            if s.x + s.w <= end {
+                self.skyline.remove(j);
+            } else {
+                s.w = s.x + s.w - end;
+                s.x = end;
+                break;
+            }
+        }
+        let mut k = 0;
+        while k + 1 < self.skyline.len() {
+            if self.skyline[k].y == self.skyline[k + 1].y {
+                self.skyline[k].w += self.skyline[k + 1].w;
+                self.skyline.remove(k + 1);
+            } else {
+                k += 1;
+            }
+        }
+    }
+}
+
+#[cfg(feature="alloc")]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Free {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[cfg(feature="alloc")]
+impl Free {
+    fn contains(self, other: Free) -> bool {
+        other.x >= self.x && other.y >= self.y && other.x + other.w <= self.x + self.w && other.y + other.h <= self.y + self.h
+    }
+
+    fn intersects(self, other: Free) -> bool {
+        self.x < other.x + other.w && other.x < self.x + self.w && self.y < other.y + other.h && other.y < self.y + self.h
+    }
+}
+
+/// Maximal rectangles packer with the best short side fit heuristic.
+#[cfg(feature="alloc")]
+#[derive(Debug, Clone)]
+pub struct MaxRectsPacker {
+    container: Rect,
+    free: Vec<Free>,
+}
+
+#[cfg(feature="alloc")]
+impl MaxRectsPacker {
+    pub fn new(container: Rect) -> Self {
+        let (w, h) = dims(container.size);
+        let mut free = Vec::new();
+        if w != 0 && h != 0 {
+            free.push(Free { x: 0, y: 0, w, h });
+        }
+        MaxRectsPacker { container, free }
+    }
+
+    pub fn container(&self) -> Rect { self.container }
+
+    pub fn insert(&mut self, size: Vector, allow_rotation: bool) -> Option<Packed> {
+        let (cw, ch) = dims(self.container.size);
+        let mut best: Option<((u32, u32), Free, bool)> = None;
+        for (w, h, rotated) in orientations(size, allow_rotation) {
+            if w == 0 || h == 0 {
+                if w <= cw && h <= ch { return Some(packed(self.container, 0, 0, w, h, rotated)); }
+                continue;
+            }
+            for f in &self.free {
+                if w > f.w || h > f.h { continue; }
+                let (dw, dh) = (f.w - w, f.h - h);
+                let score = (dw.min(dh), dw.max(dh));
+                if best.is_none_or(|b| score < b.0) {
+                    best = Some((score, Free { x: f.x, y: f.y, w, h }, rotated));
+                }
+            }
+        }
+        let (_, used, rotated) = best?;
+        self.split(used);
+        Some(packed(self.container, used.x, used.y, used.w, used.h, rotated))
+    }
+
+    fn split(&mut self, used: Free) {
+        let mut i = 0;
+        while i < self.free.len() {
+            let f = self.free[i];
+            if !f.intersects(used) {
+                i += 1;
+                continue;
+            }
+            self.free.swap_remove(i);
+            if used.x > f.x {
+                self.free.push(Free { x: f.x, y: f.y, w: used.x - f.x, h: f.h });
+            }
+            if used.x + used.w < f.x + f.w {
+                self.free.push(Free { x: used.x + used.w, y: f.y, w: f.x + f.w - used.x - used.w, h: f.h });
+            }
+            if used.y > f.y {
+                self.free.push(Free { x: f.x, y: f.y, w: f.w, h: used.y - f.y });
+            }
+            if used.y + used.h < f.y + f.h {
+                self.free.push(Free { x: f.x, y: used.y + used.h, w: f.w, h: f.y + f.h - used.y - used.h });
+            }
+        }
+        let mut i = 0;
+        while i < self.free.len() {
+            let f = self.free[i];
+            if self.free.iter().enumerate().any(|(j, &g)| j != i && g.contains(f) && (g != f || j < i)) {
+                self.free.swap_remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+#[cfg(feature="alloc")]
+macro_attr! {
+    #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
+    #[derive(EnumDisplay!, EnumFromStr!)]
+    pub enum PackStrategy { Shelf, Skyline, MaxRects }
+}
+
+/// Packs all `sizes` into the `container`, larger items first.
+///
+/// Returns the placement for every item in the original order, or `None` for items that do not fit.
+#[cfg(feature="alloc")]
+pub fn pack(container: Rect, sizes: &[Vector], strategy: PackStrategy, allow_rotation: bool) -> Vec<Option<Packed>> {
+    let mut order: Vec<usize> = (0 .. sizes.len()).collect();
+    match strategy {
+        PackStrategy::Shelf => order.sort_by_key(|&i| {
+            let (w, h) = dims(sizes[i]);
+            Reverse(if allow_rotation { w.min(h) } else { h })
+        }),
+        _ => order.sort_by_key(|&i| {
+            let (w, h) = dims(sizes[i]);
+            Reverse((w.max(h), w.min(h)))
+        }),
+    }
+    match strategy {
+        PackStrategy::Shelf => {
+            let mut packer = ShelfPacker::new(container);
+            pack_in_order(sizes, &order, |size| packer.insert(size, allow_rotation))
+        },
+        PackStrategy::Skyline => {
+            let mut packer = SkylinePacker::new(container);
+            pack_in_order(sizes, &order, |size| packer.insert(size, allow_rotation))
+        },
+        PackStrategy::MaxRects => {
+            let mut packer = MaxRectsPacker::new(container);
+            pack_in_order(sizes, &order, |size| packer.insert(size, allow_rotation))
+        },
+    }
+}
+
+#[cfg(feature="alloc")]
+fn pack_in_order(sizes: &[Vector], order: &[usize], mut insert: impl FnMut(Vector) -> Option<Packed>) -> Vec<Option<Packed>> {
+    let mut res = vec![None; sizes.len()];
+    for &i in order {
+        res[i] = insert(sizes[i]);
+    }
+    res
+}
+
+#[cfg(all(test, feature="alloc"))]
+mod tests {
+    use alloc::vec::Vec;
+    use quickcheck::TestResult;
+    use quickcheck_macros::quickcheck;
+    use crate::*;
+    use crate::pack::*;
+
+    fn check(container: Rect, sizes: &[Vector], strategy: PackStrategy, allow_rotation: bool) -> bool {
+        let packed = pack(container, sizes, strategy, allow_rotation);
+        let rects: Vec<Rect> = packed.iter().flatten().map(|x| x.rect).collect();
+        packed.iter().zip(sizes).all(|(p, &s)| p.is_none_or(|p| {
+            let size = if p.rotated { Vector { x: s.y, y: s.x } } else { s };
+            p.rect.size == size && (p.rect.is_empty() || container.intersect(p.rect) == p.rect)
+        })) && rects.iter().enumerate().all(|(i, a)| {
+            rects[i + 1 ..].iter().all(|b| a.intersect(*b).is_empty())
+        })
+    }
+
+    fn sizes(sizes: Vec<(u8, u8)>) -> Vec<Vector> {
+        sizes.into_iter().map(|(x, y)| Vector { x: (x % 20) as i16, y: (y % 20) as i16 }).collect()
+    }
+
+    #[quickcheck]
+    fn pack_no_intersections(tl: Point, w: u8, h: u8, s: Vec<(u8, u8)>, allow_rotation: bool) -> TestResult {
+        let container = Rect { tl, size: Vector { x: w as i16, y: h as i16 } };
+        let s = sizes(s);
+        TestResult::from_bool(
+            check(container, &s, PackStrategy::Shelf, allow_rotation) &&
+            check(container, &s, PackStrategy::Skyline, allow_rotation) &&
+            check(container, &s, PackStrategy::MaxRects, allow_rotation)
+        )
+    }
+
+    #[test]
+    fn pack_exact_fit() {
+        let container = Rect { tl: Point { x: -2, y: 3 }, size: Vector { x: 4, y: 4 } };
+        let sizes = [Vector { x: 2, y: 2 }; 4];
+        for strategy in [PackStrategy::Shelf, PackStrategy::Skyline, PackStrategy::MaxRects] {
+            assert!(pack(container, &sizes, strategy, false).iter().all(|x| x.is_some()));
+            assert!(pack(container, &[Vector { x: 5, y: 1 }], strategy, false)[0].is_none());
+        }
+    }
+
+    #[test]
+    fn pack_rotation() {
+        let container = Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: 2, y: 6 } };
+        for strategy in [PackStrategy::Shelf, PackStrategy::Skyline, PackStrategy::MaxRects] {
+            let res = pack(container, &[Vector { x: 6, y: 2 }], strategy, true);
+            assert_eq!(res[0].map(|x| x.rotated), Some(true));
+            assert!(pack(container, &[Vector { x: 6, y: 2 }], strategy, false)[0].is_none());
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Integer scaling between coordinate spaces.
+
+use core::num::NonZeroU16;
+use enum_derive_2018::{EnumDisplay, EnumFromStr};
+use macro_attr_2018::macro_attr;
+use crate::{Point, Rect, Vector};
+
+macro_attr! {
+    /// `Outward` rounds rect edges away from the rect (so the result covers the source)
+    /// and single coordinates away from zero.
+    #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
+    #[derive(EnumDisplay!, EnumFromStr!)]
+    pub enum Rounding { Floor, Ceil, Outward }
+}
+
+fn div(n: i32, d: i32, rounding: Rounding, far: bool) -> i32 {
+    let q = n.div_euclid(d);
+    let exact = n.rem_euclid(d) == 0;
+    let ceil = match rounding {
+        Rounding::Floor => false,
+        Rounding::Ceil => true,
+        Rounding::Outward => far,
+    };
+    if ceil && !exact { q + 1 } else { q }
+}
+
+fn coord(n: i32) -> Option<i16> { i16::try_from(n).ok() }
+
+/// Exact integer scale, e.g. terminal cells to pixels.
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
+pub struct Scale {
+    x: NonZeroU16,
+    y: NonZeroU16,
+}
+
+impl Scale {
+    pub const fn new(x: u16, y: u16) -> Self {
+        assert!(x != 0 && y != 0);
+        Scale { x: NonZeroU16::new(x).unwrap(), y: NonZeroU16::new(y).unwrap() }
+    }
+
+    pub fn x(self) -> u16 { self.x.get() }
+
+    pub fn y(self) -> u16 { self.y.get() }
+
+    pub fn transform_point(self, p: Point) -> Option<Point> {
+        self.to_rational().transform_point(p, Rounding::Floor)
+    }
+
+    pub fn transform_vector(self, v: Vector) -> Option<Vector> {
+        self.to_rational().transform_vector(v, Rounding::Floor)
+    }
+
+    /// Returns `None` if the result does not fit, or if `r` wraps around the plane.
+    pub fn transform_rect(self, r: Rect) -> Option<Rect> {
+        self.to_rational().transform_rect(r, Rounding::Floor)
+    }
+
+    pub fn to_rational(self) -> RationalScale {
+        RationalScale { num_x: self.x, den_x: NonZeroU16::MIN, num_y: self.y, den_y: NonZeroU16::MIN }
+    }
+
+    /// The scale back, e.g. from pixels to cells.
+    pub fn inverse(self) -> RationalScale { self.to_rational().inverse() }
+}
+
+/// Scale by `num / den` per axis, e.g. a 512×512 map to a 64×64 minimap.
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
+pub struct RationalScale {
+    num_x: NonZeroU16,
+    den_x: NonZeroU16,
+    num_y: NonZeroU16,
+    den_y: NonZeroU16,
+}
+
+impl RationalScale {
+    pub const fn new(num_x: u16, den_x: u16, num_y: u16, den_y: u16) -> Self {
+        assert!(num_x != 0 && den_x != 0 && num_y != 0 && den_y != 0);
+        RationalScale {
+            num_x: NonZeroU16::new(num_x).unwrap(),
+            den_x: NonZeroU16::new(den_x).unwrap(),
+            num_y: NonZeroU16::new(num_y).unwrap(),
+            den_y: NonZeroU16::new(den_y).unwrap(),
+        }
+    }
+
+    pub fn inverse(self) -> RationalScale {
+        RationalScale { num_x: self.den_x, den_x: self.num_x, num_y: self.den_y, den_y: self.num_y }
+    }
+
+    fn x(self, x: i32, rounding: Rounding, far: bool) -> i32 {
+        div(x * self.num_x.get() as i32, self.den_x.get() as i32, rounding, far)
+    }
+
+    fn y(self, y: i32, rounding: Rounding, far: bool) -> i32 {
+        div(y * self.num_y.get() as i32, self.den_y.get() as i32, rounding, far)
+    }
+
+    pub fn transform_point(self, p: Point, rounding: Rounding) -> Option<Point> {
+        Some(Point {
+            x: coord(self.x(p.x as i32, rounding, p.x > 0))?,
+            y: coord(self.y(p.y as i32, rounding, p.y > 0))?,
+        })
+    }
+
+    pub fn transform_vector(self, v: Vector, rounding: Rounding) -> Option<Vector> {
+        let p = self.transform_point(Point { x: v.x, y: v.y }, rounding)?;
+        Some(Vector { x: p.x, y: p.y })
+    }
+
+    /// Returns `None` if the result does not fit, or if `rect` wraps around the plane.
+    pub fn transform_rect(self, rect: Rect, rounding: Rounding) -> Option<Rect> {
+        let tl = Point {
+            x: coord(self.x(rect.l() as i32, rounding, false))?,
+            y: coord(self.y(rect.t() as i32, rounding, false))?,
+        };
+        let br = Point {
+            x: coord(self.x(rect.checked_r()? as i32, rounding, true))?,
+            y: coord(self.y(rect.checked_b()? as i32, rounding, true))?,
+        };
+        Some(Rect::from_tl_br(tl, br))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::TestResult;
+    use quickcheck_macros::quickcheck;
+    use crate::*;
+    use crate::scale::*;
+
+    #[quickcheck]
+    fn outward_rect_covers_source(r: Rect, num: (u8, u8), den: (u8, u8)) -> TestResult {
+        if r.checked_r().is_none() || r.checked_b().is_none() { return TestResult::discard(); }
+        let s = RationalScale::new(num.0 as u16 + 1, den.0 as u16 + 1, num.1 as u16 + 1, den.1 as u16 + 1);
+        let Some(scaled) = s.transform_rect(r, Rounding::Outward) else { return TestResult::discard(); };
+        let Some(back) = s.inverse().transform_rect(scaled, Rounding::Outward) else { return TestResult::discard(); };
+        TestResult::from_bool(r.is_empty() || back.intersect(r) == r)
+    }
+
+    #[quickcheck]
+    fn scale_round_trip(p: Point, x: u8, y: u8) -> bool {
+        let s = Scale::new(x as u16 + 1, y as u16 + 1);
+        s.transform_point(p).is_none_or(|q| s.inverse().transform_point(q, Rounding::Floor) == Some(p))
+    }
+
+    #[test]
+    fn minimap() {
+        let s = RationalScale::new(64, 512, 64, 512);
+        let r = Rect { tl: Point { x: -3, y: 3 }, size: Vector { x: 2, y: 6 } };
+        assert_eq!(s.transform_rect(r, Rounding::Outward), Some(Rect { tl: Point { x: -1, y: 0 }, size: Vector { x: 1, y: 2 } }));
+        assert_eq!(s.transform_rect(r, Rounding::Floor), Some(Rect { tl: Point { x: -1, y: 0 }, size: Vector { x: 0, y: 1 } }));
+        assert_eq!(s.transform_point(Point { x: -9, y: 9 }, Rounding::Ceil), Some(Point { x: -1, y: 2 }));
+        assert_eq!(s.transform_point(Point { x: -9, y: 9 }, Rounding::Outward), Some(Point { x: -2, y: 2 }));
+    }
+
+    #[test]
+    fn cells_to_pixels() {
+        let s = Scale::new(8, 16);
+        let r = Rect { tl: Point { x: 1, y: 2 }, size: Vector { x: 3, y: 1 } };
+        assert_eq!(s.transform_rect(r), Some(Rect { tl: Point { x: 8, y: 32 }, size: Vector { x: 24, y: 16 } }));
+        assert_eq!(s.transform_point(Point { x: 5000, y: 0 }), None);
+        assert_eq!(s.inverse().transform_point(Point { x: -1, y: 17 }, Rounding::Floor), Some(Point { x: -1, y: 1 }));
+    }
+}
@@ -0,0 +1,213 @@
+//! Integer affine transforms.
+
+#[cfg(feature="alloc")]
+use alloc::vec::Vec;
+use core::cmp::{min, max};
+use crate::{Point, Rect, Vector};
+
+/// The `[a b tx; c d ty]` matrix: `x' = a x + b y + tx`, `y' = c x + d y + ty`.
+///
+/// All arithmetic wraps, as elsewhere in the crate.
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
+pub struct Transform2d {
+    pub a: i16,
+    pub b: i16,
+    pub c: i16,
+    pub d: i16,
+    pub t: Vector,
+}
+
+impl Transform2d {
+    pub const fn identity() -> Self { Transform2d { a: 1, b: 0, c: 0, d: 1, t: Vector::null() } }
+
+    pub const fn translation(t: Vector) -> Self { Transform2d { a: 1, b: 0, c: 0, d: 1, t } }
+
+    pub const fn scale(sx: i16, sy: i16) -> Self { Transform2d { a: sx, b: 0, c: 0, d: sy, t: Vector::null() } }
+
+    /// `x' = x + k y`.
+    pub const fn shear_x(k: i16) -> Self { Transform2d { a: 1, b: k, c: 0, d: 1, t: Vector::null() } }
+
+    /// `y' = y + k x`.
+    pub const fn shear_y(k: i16) -> Self { Transform2d { a: 1, b: 0, c: k, d: 1, t: Vector::null() } }
+
+    /// Rotation by 90° clockwise on screen (the `y` axis points down).
+    pub const fn rotate_cw() -> Self { Transform2d { a: 0, b: -1, c: 1, d: 0, t: Vector::null() } }
+
+    pub const fn rotate_180() -> Self { Transform2d { a: -1, b: 0, c: 0, d: -1, t: Vector::null() } }
+
+    /// Rotation by 90° counterclockwise on screen (the `y` axis points down).
+    pub const fn rotate_ccw() -> Self { Transform2d { a: 0, b: 1, c: -1, d: 0, t: Vector::null() } }
+
+    pub fn determinant(self) -> i32 {
+        self.a as i32 * self.d as i32 - self.b as i32 * self.c as i32
+    }
+
+    pub fn transform_vector(self, v: Vector) -> Vector {
+        Vector {
+            x: self.a.wrapping_mul(v.x).wrapping_add(self.b.wrapping_mul(v.y)),
+            y: self.c.wrapping_mul(v.x).wrapping_add(self.d.wrapping_mul(v.y)),
+        }
+    }
+
+    pub fn transform_point(self, p: Point) -> Point {
+        let v = self.transform_vector(Vector { x: p.x, y: p.y }) + self.t;
+        Point { x: v.x, y: v.y }
+    }
+
+    pub fn checked_transform_point(self, p: Point) -> Option<Point> {
+        let x = self.a as i64 * p.x as i64 + self.b as i64 * p.y as i64 + self.t.x as i64;
+        let y = self.c as i64 * p.x as i64 + self.d as i64 * p.y as i64 + self.t.y as i64;
+        Some(Point { x: i16::try_from(x).ok()?, y: i16::try_from(y).ok()? })
+    }
+
+    /// The bounding rect of the transformed rect points.
+    pub fn transform_rect(self, r: Rect) -> Rect {
+        if r.is_empty() {
+            return Rect { tl: self.transform_point(r.tl), size: Vector::null() };
+        }
+        let corners = [r.tl, r.tr_inner(), r.bl_inner(), r.br_inner()].map(|p| self.transform_point(p));
+        let base = corners[0];
+        let offsets = corners.map(|p| p.offset_from(base));
+        let (l, r) = offsets.iter().fold((0, 0), |(l, r), v| (min(l, v.x), max(r, v.x)));
+        let (t, b) = offsets.iter().fold((0, 0), |(t, b), v| (min(t, v.y), max(b, v.y)));
+        let br = base.offset(Vector { x: r, y: b }).offset(Vector { x: 1, y: 1 });
+        Rect::from_tl_br(base.offset(Vector { x: l, y: t }), br)
+    }
+
+    /// Applies `self`, then `next`.
+    pub fn then(self, next: Transform2d) -> Transform2d {
+        Transform2d {
+            a: next.a.wrapping_mul(self.a).wrapping_add(next.b.wrapping_mul(self.c)),
+            b: next.a.wrapping_mul(self.b).wrapping_add(next.b.wrapping_mul(self.d)),
+            c: next.c.wrapping_mul(self.a).wrapping_add(next.d.wrapping_mul(self.c)),
+            d: next.c.wrapping_mul(self.b).wrapping_add(next.d.wrapping_mul(self.d)),
+            t: next.transform_vector(self.t) + next.t,
+        }
+    }
+
+    /// Returns `None` if the matrix is not unimodular, i.e. the determinant is not ±1.
+    pub fn inverse(self) -> Option<Transform2d> {
+        let det = match self.determinant() {
+            1 => 1i16,
+            -1 => -1i16,
+            _ => return None,
+        };
+        let linear = Transform2d {
+            a: self.d.wrapping_mul(det),
+            b: self.b.wrapping_neg().wrapping_mul(det),
+            c: self.c.wrapping_neg().wrapping_mul(det),
+            d: self.a.wrapping_mul(det),
+            t: Vector::null(),
+        };
+        Some(Transform2d { t: -linear.transform_vector(self.t), ..linear })
+    }
+}
+
+impl Default for Transform2d {
+    fn default() -> Self { Transform2d::identity() }
+}
+
+/// A stack of nested coordinate frames.
+#[cfg(feature="alloc")]
+#[derive(Debug, Clone, Default)]
+pub struct TransformStack {
+    /// The pushed local transforms along with the composed ones.
+    stack: Vec<(Transform2d, Transform2d)>,
+}
+
+#[cfg(feature="alloc")]
+impl TransformStack {
+    pub fn new() -> Self { TransformStack { stack: Vec::new() } }
+
+    /// The transform from the innermost frame to the outermost one.
+    pub fn current(&self) -> Transform2d {
+        self.stack.last().map_or_else(Transform2d::identity, |x| x.1)
+    }
+
+    /// Enters a frame; `local` maps the new frame coordinates to the current frame ones.
+    pub fn push(&mut self, local: Transform2d) {
+        let current = self.current();
+        self.stack.push((local, local.then(current)));
+    }
+
+    /// Leaves the innermost frame and returns its `local` transform as it was pushed.
+    pub fn pop(&mut self) -> Option<Transform2d> {
+        self.stack.pop().map(|x| x.0)
+    }
+
+    pub fn depth(&self) -> usize { self.stack.len() }
+}
+
+#[cfg(test)]
+impl quickcheck::Arbitrary for Transform2d {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let a = <(i16, i16, i16, i16, Vector)>::arbitrary(g);
+        Transform2d { a: a.0, b: a.1, c: a.2, d: a.3, t: a.4 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use crate::*;
+    use crate::transform::*;
+
+    #[quickcheck]
+    fn transform_composition(m: Transform2d, n: Transform2d, p: Point) -> bool {
+        m.then(n).transform_point(p) == n.transform_point(m.transform_point(p))
+    }
+
+    #[quickcheck]
+    fn transform_inverse(m: Transform2d, p: Point) -> bool {
+        let m = Transform2d { a: 1, c: 0, d: 1, ..m };
+        let i = m.inverse().unwrap();
+        i.transform_point(m.transform_point(p)) == p && m.then(i) == Transform2d::identity()
+    }
+
+    #[quickcheck]
+    fn checked_transform_agrees_with_wrapping(m: Transform2d, p: Point, extreme: (bool, bool, bool)) -> bool {
+        let pick = |x: i16, min: bool| if min { i16::MIN } else { x };
+        let m = Transform2d { a: pick(m.a, extreme.0), b: pick(m.b, extreme.0), ..m };
+        let p = Point { x: pick(p.x, extreme.1), y: pick(p.y, extreme.1) };
+        let m = if extreme.2 { Transform2d { t: Vector { x: i16::MAX, y: i16::MIN }, ..m } } else { m };
+        let exact = |k: [i16; 3]| k[0] as i64 * p.x as i64 + k[1] as i64 * p.y as i64 + k[2] as i64;
+        let fits = |v: i64| i16::try_from(v).is_ok();
+        let expected = fits(exact([m.a, m.b, m.t.x])) && fits(exact([m.c, m.d, m.t.y]));
+        match m.checked_transform_point(p) {
+            Some(q) => expected && q == m.transform_point(p),
+            None => !expected,
+        }
+    }
+
+    #[quickcheck]
+    fn transform_rect_contains_points(r: Rect, t: Vector, k: i8) -> bool {
+        let r = Rect { tl: r.tl, size: r.size.min(Vector { x: 8, y: 8 }) };
+        let m = Transform2d::shear_x(k as i16).then(Transform2d::rotate_cw()).then(Transform2d::translation(t));
+        let bounds = m.transform_rect(r);
+        r.points().all(|p| bounds.contains(m.transform_point(p)))
+    }
+
+    #[test]
+    fn transform_rotations() {
+        let p = Point { x: 3, y: 1 };
+        assert_eq!(Transform2d::rotate_cw().transform_point(p), Point { x: -1, y: 3 });
+        assert_eq!(Transform2d::rotate_cw().then(Transform2d::rotate_cw()), Transform2d::rotate_180());
+        assert_eq!(Transform2d::rotate_cw().inverse(), Some(Transform2d::rotate_ccw()));
+        assert_eq!(Transform2d::scale(2, 1).inverse(), None);
+        let r = Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: 2, y: 1 } };
+        assert_eq!(Transform2d::rotate_cw().transform_rect(r), Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: 1, y: 2 } });
+        let min = Transform2d { a: i16::MIN, b: i16::MIN, c: 0, d: 0, t: Vector::null() };
+        assert_eq!(min.checked_transform_point(Point { x: i16::MIN, y: i16::MIN }), None);
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn transform_stack() {
+        let mut stack = TransformStack::new();
+        stack.push(Transform2d::translation(Vector { x: 10, y: 10 }));
+        stack.push(Transform2d::scale(2, 2));
+        assert_eq!(stack.current().transform_point(Point { x: 1, y: 1 }), Point { x: 12, y: 12 });
+        assert_eq!(stack.pop(), Some(Transform2d::scale(2, 2)));
+        assert_eq!(stack.current().transform_point(Point { x: 1, y: 1 }), Point { x: 11, y: 11 });
+    }
+}
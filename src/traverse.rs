@@ -0,0 +1,423 @@
+//! Alternative orders of visiting rect points.
+
+use core::iter::FusedIterator;
+use crate::{exact_size_hint, Point, Rect};
+
+fn local(rect: Rect, x: u32, y: u32) -> Point {
+    Point { x: rect.l().wrapping_add(x as u16 as i16), y: rect.t().wrapping_add(y as u16 as i16) }
+}
+
+macro_rules! indexed_points {
+    ($name:ident) => {
+        impl Iterator for $name {
+            type Item = Point;
+
+            fn next(&mut self) -> Option<Point> {
+                if self.front == self.back {
+                    return None;
+                }
+                let item = self.point(self.front);
+                self.front += 1;
+                Some(item)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) { exact_size_hint(self.back - self.front) }
+        }
+
+        impl DoubleEndedIterator for $name {
+            fn next_back(&mut self) -> Option<Point> {
+                if self.front == self.back {
+                    return None;
+                }
+                self.back -= 1;
+                Some(self.point(self.back))
+            }
+        }
+
+        #[cfg(not(target_pointer_width="16"))]
+        impl ExactSizeIterator for $name { }
+
+        impl FusedIterator for $name { }
+    };
+}
+
+/// Visits rect points column by column, top to bottom.
+#[derive(Debug, Clone)]
+pub struct ColumnMajorPoints {
+    rect: Rect,
+    front: u32,
+    back: u32,
+}
+
+impl ColumnMajorPoints {
+    pub(crate) fn new(rect: Rect) -> Self { ColumnMajorPoints { rect, front: 0, back: rect.area() } }
+
+    fn point(&self, index: u32) -> Point {
+        let h = self.rect.h() as u16 as u32;
+        local(self.rect, index / h, index % h)
+    }
+}
+
+indexed_points!(ColumnMajorPoints);
+
+/// Visits rect points row by row, even rows (counting from the top one) left to right,
+/// odd rows right to left.
+#[derive(Debug, Clone)]
+pub struct SerpentinePoints {
+    rect: Rect,
+    front: u32,
+    back: u32,
+}
+
+impl SerpentinePoints {
+    pub(crate) fn new(rect: Rect) -> Self { SerpentinePoints { rect, front: 0, back: rect.area() } }
+
+    fn point(&self, index: u32) -> Point {
+        let w = self.rect.w() as u16 as u32;
+        let (x, y) = (index % w, index / w);
+        local(self.rect, if y % 2 == 0 { x } else { w - 1 - x }, y)
+    }
+}
+
+indexed_points!(SerpentinePoints);
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    x: i32,
+    y: i32,
+    dx: i32,
+    dy: i32,
+    len: i32,
+}
+
+impl Segment {
+    /// Clips the `from ..= to` run at `fixed` across to `0 .. w` × `0 .. h`.
+    fn clip(horizontal: bool, fixed: i32, from: i32, to: i32, w: i32, h: i32) -> Option<Segment> {
+        let (along, across) = if horizontal { (w, h) } else { (h, w) };
+        if fixed < 0 || fixed >= across { return None; }
+        let lo = from.min(to).max(0);
+        let hi = from.max(to).min(along - 1);
+        if lo > hi { return None; }
+        let (start, d) = if from <= to { (lo, 1) } else { (hi, -1) };
+        Some(if horizontal {
+            Segment { x: start, y: fixed, dx: d, dy: 0, len: hi - lo + 1 }
+        } else {
+            Segment { x: fixed, y: start, dx: 0, dy: d, len: hi - lo + 1 }
+        })
+    }
+}
+
+/// Walks ring by ring, every ring clockwise, visiting each ring side as a clipped segment.
+#[derive(Debug, Clone)]
+struct RingWalk {
+    rect: Rect,
+    inward: bool,
+    ring: i32,
+    side: u8,
+    segment: Segment,
+    remaining: u32,
+}
+
+impl RingWalk {
+    fn new(rect: Rect, inward: bool) -> Self {
+        let empty = Segment { x: 0, y: 0, dx: 0, dy: 0, len: 0 };
+        RingWalk { rect, inward, ring: 0, side: 0, segment: empty, remaining: rect.area() }
+    }
+
+    fn side(&self) -> Option<Segment> {
+        let w = self.rect.w() as u16 as i32;
+        let h = self.rect.h() as u16 as i32;
+        let k = self.ring;
+        if self.inward {
+            let (l, t, r, b) = (k, k, w - 1 - k, h - 1 - k);
+            match self.side {
+                0 => Segment::clip(true, t, l, r, w, h),
+                1 if b > t => Segment::clip(false, r, t + 1, b, w, h),
+                2 if b > t && r > l => Segment::clip(true, b, r - 1, l, w, h),
+                3 if r > l && b > t + 1 => Segment::clip(false, l, b - 1, t + 1, w, h),
+                _ => None,
+            }
+        } else {
+            let (cx, cy) = ((w - 1) / 2, (h - 1) / 2);
+            if k == 0 {
+                return if self.side == 0 { Segment::clip(false, cx, cy, cy, w, h) } else { None };
+            }
+            match self.side {
+                0 => Segment::clip(false, cx + k, cy - k + 1, cy + k, w, h),
+                1 => Segment::clip(true, cy + k, cx + k - 1, cx - k, w, h),
+                2 => Segment::clip(false, cx - k, cy + k - 1, cy - k, w, h),
+                _ => Segment::clip(true, cy - k, cx - k + 1, cx + k, w, h),
+            }
+        }
+    }
+
+    fn next(&mut self) -> Option<Point> {
+        if self.remaining == 0 { return None; }
+        while self.segment.len == 0 {
+            if let Some(segment) = self.side() {
+                self.segment = segment;
+            }
+            self.side += 1;
+            if self.side == 4 {
+                self.side = 0;
+                self.ring += 1;
+            }
+        }
+        let s = &mut self.segment;
+        let item = local(self.rect, s.x as u32, s.y as u32);
+        s.x += s.dx;
+        s.y += s.dy;
+        s.len -= 1;
+        self.remaining -= 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { exact_size_hint(self.remaining) }
+}
+
+/// Visits rect points in an outward clockwise spiral.
+///
+/// The spiral starts at the rect center, rounded to the top left for even sizes,
+/// and then goes right, down, left, up, with parts outside the rect skipped.
+/// Points are visited in non-decreasing Chebyshev distance from the center.
+#[derive(Debug, Clone)]
+pub struct SpiralPoints(RingWalk);
+
+impl SpiralPoints {
+    pub(crate) fn new(rect: Rect) -> Self { SpiralPoints(RingWalk::new(rect, false)) }
+}
+
+impl Iterator for SpiralPoints {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> { self.0.next() }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+}
+
+#[cfg(not(target_pointer_width="16"))]
+impl ExactSizeIterator for SpiralPoints { }
+
+impl FusedIterator for SpiralPoints { }
+
+/// Visits rect points ring by ring from the rect border inward,
+/// every ring clockwise starting at its top left point.
+#[derive(Debug, Clone)]
+pub struct RingPoints(RingWalk);
+
+impl RingPoints {
+    pub(crate) fn new(rect: Rect) -> Self { RingPoints(RingWalk::new(rect, true)) }
+}
+
+impl Iterator for RingPoints {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> { self.0.next() }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+}
+
+#[cfg(not(target_pointer_width="16"))]
+impl ExactSizeIterator for RingPoints { }
+
+impl FusedIterator for RingPoints { }
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Quad {
+    x: u32,
+    y: u32,
+    level: u8,
+}
+
+/// Depth-first walk over the quadtree of the whole (biased) plane, skipping quads outside the rect.
+#[derive(Debug, Clone)]
+struct CurveWalk {
+    l: u32,
+    t: u32,
+    w: u32,
+    h: u32,
+    hilbert: bool,
+    stack: [Quad; 1 + 3 * 16],
+    len: usize,
+    remaining: u32,
+}
+
+fn overlaps(start: u32, size: u32, range_start: u32, range_len: u32) -> bool {
+    let range_end = range_start + range_len;
+    (start < range_end && range_start < start + size) ||
+        (start + 0x10000 < range_end && range_start < start + 0x10000 + size)
+}
+
+impl CurveWalk {
+    fn new(rect: Rect, hilbert: bool) -> Self {
+        let mut stack = [Quad::default(); 1 + 3 * 16];
+        stack[0] = Quad { x: 0, y: 0, level: 16 };
+        CurveWalk {
+            l: rect.l() as u16 as u32 ^ 0x8000,
+            t: rect.t() as u16 as u32 ^ 0x8000,
+            w: rect.w() as u16 as u32,
+            h: rect.h() as u16 as u32,
+            hilbert,
+            stack,
+            len: if rect.is_empty() { 0 } else { 1 },
+            remaining: rect.area(),
+        }
+    }
+
+    fn key(&self, quad: Quad) -> u32 {
+        let p = Point { x: (quad.x as u16 ^ 0x8000) as i16, y: (quad.y as u16 ^ 0x8000) as i16 };
+        if self.hilbert { p.to_hilbert(16) } else { p.to_morton() }
+    }
+
+    fn next(&mut self) -> Option<Point> {
+        while self.len != 0 {
+            self.len -= 1;
+            let quad = self.stack[self.len];
+            if quad.level == 0 {
+                self.remaining -= 1;
+                return Some(Point { x: (quad.x as u16 ^ 0x8000) as i16, y: (quad.y as u16 ^ 0x8000) as i16 });
+            }
+            let level = quad.level - 1;
+            let size = 1u32 << level;
+            let mut children = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                .map(|(i, j)| Quad { x: quad.x + i * size, y: quad.y + j * size, level });
+            children.sort_unstable_by_key(|&c| core::cmp::Reverse(self.key(c)));
+            for c in children {
+                if overlaps(c.x, size, self.l, self.w) && overlaps(c.y, size, self.t, self.h) {
+                    self.stack[self.len] = c;
+                    self.len += 1;
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { exact_size_hint(self.remaining) }
+}
+
+/// See [`Rect::points_z_order`].
+#[derive(Debug, Clone)]
+pub struct ZOrderPoints(CurveWalk);
+
+impl ZOrderPoints {
+    pub(crate) fn new(rect: Rect) -> Self { ZOrderPoints(CurveWalk::new(rect, false)) }
+}
+
+impl Iterator for ZOrderPoints {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> { self.0.next() }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+}
+
+#[cfg(not(target_pointer_width="16"))]
+impl ExactSizeIterator for ZOrderPoints { }
+
+impl FusedIterator for ZOrderPoints { }
+
+/// See [`Rect::points_hilbert`].
+#[derive(Debug, Clone)]
+pub struct HilbertPoints(CurveWalk);
+
+impl HilbertPoints {
+    pub(crate) fn new(rect: Rect) -> Self { HilbertPoints(CurveWalk::new(rect, true)) }
+}
+
+impl Iterator for HilbertPoints {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> { self.0.next() }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+}
+
+#[cfg(not(target_pointer_width="16"))]
+impl ExactSizeIterator for HilbertPoints { }
+
+impl FusedIterator for HilbertPoints { }
+
+#[cfg(all(test, feature="alloc"))]
+mod tests {
+    use alloc::vec::Vec;
+    use quickcheck::TestResult;
+    use quickcheck_macros::quickcheck;
+    use crate::*;
+
+    fn same_points(r: Rect, points: impl Iterator<Item=Point>) -> bool {
+        let mut points: Vec<_> = points.collect();
+        let mut expected: Vec<_> = r.points().collect();
+        points.sort_by_key(|p| (p.y, p.x));
+        expected.sort_by_key(|p| (p.y, p.x));
+        points == expected
+    }
+
+    #[quickcheck]
+    fn traversals_visit_every_point_once(r: Rect) -> TestResult {
+        if r.area() > 4000 { return TestResult::discard(); }
+        let len = r.area() as usize;
+        TestResult::from_bool(
+            r.points_column_major().len() == len && r.points_serpentine().len() == len &&
+            r.points_spiral().len() == len && r.points_rings().len() == len &&
+            same_points(r, r.points_column_major()) && same_points(r, r.points_column_major().rev()) &&
+            same_points(r, r.points_serpentine()) && same_points(r, r.points_rev()) &&
+            same_points(r, r.points_spiral()) && same_points(r, r.points_rings())
+        )
+    }
+
+    #[quickcheck]
+    fn serpentine_and_square_spiral_steps_are_adjacent(r: Rect) -> TestResult {
+        if r.area() > 4000 { return TestResult::discard(); }
+        let side = (r.w() as u16 % 64) as i16;
+        let square: Vec<_> = Rect { tl: r.tl, size: Vector { x: side, y: side } }.points_spiral().collect();
+        let serpentine: Vec<_> = r.points_serpentine().collect();
+        let rings: Vec<_> = r.points_rings().collect();
+        let w = r.w() as u16 as i32;
+        let h = r.h() as u16 as i32;
+        let center = Point { x: r.l().wrapping_add(((w - 1) / 2) as i16), y: r.t().wrapping_add(((h - 1) / 2) as i16) };
+        let distance = |p: Point| {
+            let d = p.offset_from(center);
+            (d.x as i32).abs().max((d.y as i32).abs())
+        };
+        let step = |a: &Point, b: &Point| {
+            let d = b.offset_from(*a);
+            (d.x as i32).abs() + (d.y as i32).abs()
+        };
+        TestResult::from_bool(
+            serpentine.windows(2).all(|x| step(&x[0], &x[1]) == 1) &&
+            square.windows(2).all(|x| step(&x[0], &x[1]) == 1) &&
+            rings.first().is_none_or(|&p| p == r.tl) &&
+            r.points_spiral().map(distance).is_sorted()
+        )
+    }
+
+    #[quickcheck]
+    fn curve_orders_are_sorted(r: Rect) -> TestResult {
+        if r.area() > 4000 { return TestResult::discard(); }
+        let z: Vec<_> = r.points_z_order().collect();
+        let hilbert: Vec<_> = r.points_hilbert().collect();
+        TestResult::from_bool(
+            r.points_z_order().len() == z.len() && r.points_hilbert().len() == hilbert.len() &&
+            same_points(r, z.iter().copied()) && same_points(r, hilbert.iter().copied()) &&
+            z.windows(2).all(|x| x[0].to_morton() < x[1].to_morton()) &&
+            hilbert.windows(2).all(|x| x[0].to_hilbert(16) < x[1].to_hilbert(16))
+        )
+    }
+
+    #[test]
+    fn traversal_orders() {
+        let r = Rect { tl: Point { x: 1, y: 1 }, size: Vector { x: 3, y: 3 } };
+        let p = |x, y| Point { x, y };
+        assert_eq!(r.points_column_major().take(4).collect::<Vec<_>>(), [p(1, 1), p(1, 2), p(1, 3), p(2, 1)]);
+        assert_eq!(r.points_serpentine().skip(2).take(3).collect::<Vec<_>>(), [p(3, 1), p(3, 2), p(2, 2)]);
+        assert_eq!(r.points_rev().next(), Some(p(3, 3)));
+        assert_eq!(
+            r.points_spiral().collect::<Vec<_>>(),
+            [p(2, 2), p(3, 2), p(3, 3), p(2, 3), p(1, 3), p(1, 2), p(1, 1), p(2, 1), p(3, 1)]
+        );
+        assert_eq!(
+            r.points_rings().collect::<Vec<_>>(),
+            [p(1, 1), p(2, 1), p(3, 1), p(3, 2), p(3, 3), p(2, 3), p(1, 3), p(1, 2), p(2, 2)]
+        );
+    }
+}
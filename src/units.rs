@@ -0,0 +1,306 @@
+//! Unit-tagged wrappers preventing mixing points from different coordinate spaces.
+//!
+//! The unit is a phantom type parameter, e.g. an empty `enum Screen { }`.
+//! Untyped [`Point`], [`Vector`] and [`Rect`] are kept as is,
+//! typed values are converted from and to them explicitly.
+//! Nested spaces are declared with [`LocalUnit`].
+
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{Add, AddAssign, Sub, SubAssign, Neg};
+use crate::{Point, Rect, RectPoints, Vector};
+
+/// A unit of coordinates relative to a point of the `Parent` space.
+///
+/// Only matching spaces convert into each other:
+///
+/// ```
+/// use int_vec_2d::units::{LocalUnit, TypedPoint};
+///
+/// enum Screen { }
+/// enum Window { }
+/// impl LocalUnit for Window { type Parent = Screen; }
+///
+/// let base = TypedPoint::<Screen>::new(10, 20);
+/// let local: TypedPoint<Window> = TypedPoint::<Screen>::new(13, 24).relative_to(base);
+/// assert_eq!(local.absolute_with(base), TypedPoint::new(13, 24));
+/// ```
+///
+/// ```compile_fail
+/// use int_vec_2d::units::{LocalUnit, TypedPoint};
+///
+/// enum Screen { }
+/// enum Window { }
+/// impl LocalUnit for Window { type Parent = Screen; }
+///
+/// let base = TypedPoint::<Screen>::new(10, 20);
+/// TypedPoint::<Screen>::new(13, 24).absolute_with(base);
+/// ```
+pub trait LocalUnit {
+    type Parent;
+}
+
+macro_rules! unit_tagged {
+    ($name:ident, $untyped:ty) => {
+        impl<U> $name<U> {
+            pub const fn from_untyped(value: $untyped) -> Self { $name { value, unit: PhantomData } }
+
+            pub const fn to_untyped(self) -> $untyped { self.value }
+
+            /// Reinterprets the value in another coordinate space.
+            pub const fn cast_unit<V>(self) -> $name<V> { $name { value: self.value, unit: PhantomData } }
+        }
+
+        impl<U> Clone for $name<U> {
+            fn clone(&self) -> Self { *self }
+        }
+
+        impl<U> Copy for $name<U> { }
+
+        impl<U> PartialEq for $name<U> {
+            fn eq(&self, other: &Self) -> bool { self.value == other.value }
+        }
+
+        impl<U> Eq for $name<U> { }
+
+        impl<U> Hash for $name<U> {
+            fn hash<H: Hasher>(&self, state: &mut H) { self.value.hash(state) }
+        }
+
+        impl<U> Debug for $name<U> {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result { self.value.fmt(f) }
+        }
+
+        impl<U> From<$untyped> for $name<U> {
+            fn from(value: $untyped) -> Self { $name::from_untyped(value) }
+        }
+    };
+}
+
+pub struct TypedPoint<U> {
+    value: Point,
+    unit: PhantomData<fn() -> U>,
+}
+
+unit_tagged!(TypedPoint, Point);
+
+impl<U> TypedPoint<U> {
+    pub const fn new(x: i16, y: i16) -> Self { TypedPoint::from_untyped(Point { x, y }) }
+
+    pub fn x(self) -> i16 { self.value.x }
+
+    pub fn y(self) -> i16 { self.value.y }
+
+    pub fn offset(self, d: TypedVector<U>) -> TypedPoint<U> {
+        self.value.offset(d.value).into()
+    }
+
+    pub fn offset_from(self, other: TypedPoint<U>) -> TypedVector<U> {
+        self.value.offset_from(other.value).into()
+    }
+
+    /// Converts to the local space `L` whose origin is `base`.
+    pub fn relative_to<L: LocalUnit<Parent=U>>(self, base: TypedPoint<U>) -> TypedPoint<L> {
+        self.value.relative_to(base.value).into()
+    }
+
+    /// Converts from the local space to the parent space where the local origin is `base`.
+    pub fn absolute_with(self, base: TypedPoint<U::Parent>) -> TypedPoint<U::Parent> where U: LocalUnit {
+        self.value.absolute_with(base.value).into()
+    }
+}
+
+pub struct TypedVector<U> {
+    value: Vector,
+    unit: PhantomData<fn() -> U>,
+}
+
+unit_tagged!(TypedVector, Vector);
+
+impl<U> TypedVector<U> {
+    pub const fn new(x: i16, y: i16) -> Self { TypedVector::from_untyped(Vector { x, y }) }
+
+    pub const fn null() -> Self { TypedVector::from_untyped(Vector::null()) }
+
+    pub fn x(self) -> i16 { self.value.x }
+
+    pub fn y(self) -> i16 { self.value.y }
+}
+
+impl<U> Default for TypedVector<U> {
+    fn default() -> Self { TypedVector::null() }
+}
+
+impl<U> Add for TypedVector<U> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self { (self.value + other.value).into() }
+}
+
+impl<U> AddAssign for TypedVector<U> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<U> Sub for TypedVector<U> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self { (self.value - other.value).into() }
+}
+
+impl<U> SubAssign for TypedVector<U> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<U> Neg for TypedVector<U> {
+    type Output = Self;
+
+    fn neg(self) -> Self { (-self.value).into() }
+}
+
+pub struct TypedRect<U> {
+    value: Rect,
+    unit: PhantomData<fn() -> U>,
+}
+
+unit_tagged!(TypedRect, Rect);
+
+impl<U> TypedRect<U> {
+    pub const fn new(tl: TypedPoint<U>, size: TypedVector<U>) -> Self {
+        TypedRect::from_untyped(Rect { tl: tl.value, size: size.value })
+    }
+
+    pub fn from_tl_br(tl: TypedPoint<U>, br: TypedPoint<U>) -> Self {
+        Rect::from_tl_br(tl.value, br.value).into()
+    }
+
+    pub fn tl(self) -> TypedPoint<U> { self.value.tl.into() }
+
+    pub fn br(self) -> TypedPoint<U> { self.value.br().into() }
+
+    pub fn size(self) -> TypedVector<U> { self.value.size.into() }
+
+    pub fn is_empty(self) -> bool { self.value.is_empty() }
+
+    pub fn contains(self, p: TypedPoint<U>) -> bool { self.value.contains(p.value) }
+
+    pub fn intersect(self, other: TypedRect<U>) -> TypedRect<U> { self.value.intersect(other.value).into() }
+
+    pub fn offset(self, d: TypedVector<U>) -> TypedRect<U> { self.value.offset(d.value).into() }
+
+    /// Converts to the local space `L` whose origin is `base`.
+    pub fn relative_to<L: LocalUnit<Parent=U>>(self, base: TypedPoint<U>) -> TypedRect<L> {
+        self.value.relative_to(base.value).into()
+    }
+
+    /// Converts from the local space to the parent space where the local origin is `base`.
+    pub fn absolute_with(self, base: TypedPoint<U::Parent>) -> TypedRect<U::Parent> where U: LocalUnit {
+        self.value.absolute_with(base.value).into()
+    }
+
+    pub fn points(self) -> TypedRectPoints<U> {
+        TypedRectPoints { points: self.value.points(), unit: PhantomData }
+    }
+}
+
+pub struct TypedRectPoints<U> {
+    points: RectPoints,
+    unit: PhantomData<fn() -> U>,
+}
+
+impl<U> Iterator for TypedRectPoints<U> {
+    type Item = TypedPoint<U>;
+
+    fn next(&mut self) -> Option<TypedPoint<U>> { self.points.next().map(TypedPoint::from_untyped) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.points.size_hint() }
+}
+
+/// Translation from the `Src` space to the `Dst` space.
+pub struct Translation<Src, Dst> {
+    pub offset: Vector,
+    unit: PhantomData<fn(Src) -> Dst>,
+}
+
+impl<Src, Dst> Translation<Src, Dst> {
+    pub const fn new(offset: Vector) -> Self { Translation { offset, unit: PhantomData } }
+
+    /// The translation to the space whose origin is `origin`.
+    pub fn from_origin(origin: TypedPoint<Src>) -> Self {
+        Translation::new(Point { x: 0, y: 0 }.offset_from(origin.value))
+    }
+
+    pub fn transform_point(self, p: TypedPoint<Src>) -> TypedPoint<Dst> { p.value.offset(self.offset).into() }
+
+    pub fn transform_vector(self, v: TypedVector<Src>) -> TypedVector<Dst> { v.cast_unit() }
+
+    pub fn transform_rect(self, r: TypedRect<Src>) -> TypedRect<Dst> { r.value.offset(self.offset).into() }
+
+    pub fn inverse(self) -> Translation<Dst, Src> { Translation::new(-self.offset) }
+
+    pub fn then<Next>(self, next: Translation<Dst, Next>) -> Translation<Src, Next> {
+        Translation::new(self.offset + next.offset)
+    }
+}
+
+impl<Src, Dst> Clone for Translation<Src, Dst> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<Src, Dst> Copy for Translation<Src, Dst> { }
+
+impl<Src, Dst> PartialEq for Translation<Src, Dst> {
+    fn eq(&self, other: &Self) -> bool { self.offset == other.offset }
+}
+
+impl<Src, Dst> Eq for Translation<Src, Dst> { }
+
+impl<Src, Dst> Debug for Translation<Src, Dst> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Translation").field("offset", &self.offset).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use crate::*;
+    use crate::units::*;
+
+    enum Screen { }
+
+    enum Window { }
+
+    impl LocalUnit for Window {
+        type Parent = Screen;
+    }
+
+    #[quickcheck]
+    fn relative_absolute_round_trip(p: Point, base: Point) -> bool {
+        let p = TypedPoint::<Screen>::from_untyped(p);
+        let base = TypedPoint::<Screen>::from_untyped(base);
+        let local: TypedPoint<Window> = p.relative_to(base);
+        local.absolute_with(base) == p
+    }
+
+    #[quickcheck]
+    fn translation_agrees_with_relative_to(r: Rect, origin: Point) -> bool {
+        let r = TypedRect::<Screen>::from_untyped(r);
+        let origin = TypedPoint::<Screen>::from_untyped(origin);
+        let t = Translation::<Screen, Window>::from_origin(origin);
+        let local: TypedRect<Window> = r.relative_to(origin);
+        t.transform_rect(r) == local && local.absolute_with(origin) == r &&
+            t.inverse().transform_rect(t.transform_rect(r)) == r
+    }
+
+    #[test]
+    fn typed_rect_points() {
+        let r = TypedRect::<Window>::new(TypedPoint::new(1, 1), TypedVector::new(2, 2));
+        assert_eq!(r.points().count(), 4);
+        assert!(r.points().all(|p| r.contains(p)));
+    }
+}